@@ -1,10 +1,13 @@
 extern crate amethyst;
 extern crate amethyst_openvr;
 extern crate amethyst_xr_models;
+#[macro_use]
+extern crate log;
 
 mod tracker_system;
 
 use amethyst::core::cgmath::{Deg, Matrix4};
+use amethyst::core::shrev::{EventChannel, ReaderId};
 use amethyst::core::transform::{GlobalTransform, Transform, TransformBundle};
 use amethyst::input::{is_close_requested, is_key_down, InputBundle};
 use amethyst::prelude::*;
@@ -15,13 +18,15 @@ use amethyst::ui::UiBundle;
 use amethyst::utils::fps_counter::FPSCounterBundle;
 use amethyst::Error;
 
-use amethyst::xr::{XRBundle, XREvent};
-use amethyst_openvr::{ApplicationType, OpenVR};
+use amethyst::xr::XREvent;
+use amethyst_openvr::{ApplicationType, OpenVR, OpenVRBundle, OpenVREvent};
 
 use amethyst_xr_models::{XRTrackerModels};
 
 #[derive(Default)]
-struct VRExample;
+struct VRExample {
+    openvr_event_reader: Option<ReaderId<OpenVREvent>>,
+}
 
 impl<'a, 'b> SimpleState<'a, 'b> for VRExample {
     fn on_start(&mut self, data: StateData<GameData>) {
@@ -35,6 +40,15 @@ impl<'a, 'b> SimpleState<'a, 'b> for VRExample {
 
         world.add_resource(ActiveCamera { entity: cam });
 
+        // Only present if OpenVR was available and its bundle was added
+        if world.res.has_value::<EventChannel<OpenVREvent>>() {
+            self.openvr_event_reader = Some(
+                world
+                    .write_resource::<EventChannel<OpenVREvent>>()
+                    .register_reader(),
+            );
+        }
+
         let light1: Light = PointLight {
             intensity: 1.0,
             color: [0.9, 0.9, 0.9].into(),
@@ -68,6 +82,18 @@ impl<'a, 'b> SimpleState<'a, 'b> for VRExample {
     fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans<'a, 'b> {
         data.data.update(&data.world);
 
+        // OpenVR events aren't state events, so a quit request is picked up here rather than in
+        // `handle_event`
+        if let Some(ref mut reader) = self.openvr_event_reader {
+            let events = data.world.read_resource::<EventChannel<OpenVREvent>>();
+            if events
+                .read(reader)
+                .any(|event| *event == OpenVREvent::QuitRequested)
+            {
+                return Trans::Quit;
+            }
+        }
+
         Trans::None
     }
 }
@@ -85,8 +111,11 @@ fn main() -> Result<(), Error> {
     let mut game_data = GameDataBuilder::default();
 
     if OpenVR::is_available() {
-        let openvr = OpenVR::init(ApplicationType::Scene)?;
-        game_data = game_data.with_bundle(XRBundle::new(openvr))?;
+        let openvr = OpenVR::init(ApplicationType::Scene).map_err(|e| {
+            error!("{}", e);
+            e
+        })?;
+        game_data = game_data.with_bundle(OpenVRBundle::new(openvr))?;
     }
 
     game_data = game_data
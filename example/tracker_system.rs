@@ -7,6 +7,7 @@ use amethyst::renderer::{
 };
 use amethyst::xr::XREvent;
 
+use amethyst_openvr::{HapticRequest, HapticTarget, OpenVRControllerStates, BUTTON_TRIGGER};
 use amethyst_xr_models::XRModelEnabled;
 
 #[derive(Default)]
@@ -24,6 +25,8 @@ impl<'a> System<'a> for TrackerSystem {
         Read<'a, AssetStorage<Texture>>,
         ReadExpect<'a, MaterialDefaults>,
         ReadExpect<'a, ActiveCamera>,
+        Read<'a, OpenVRControllerStates>,
+        Write<'a, EventChannel<HapticRequest>>,
     );
 
     fn run(&mut self, system_data: Self::SystemData) {
@@ -36,8 +39,17 @@ impl<'a> System<'a> for TrackerSystem {
             textures,
             material_defaults,
             active_camera,
+            controllers,
+            mut haptic_requests,
         ) = system_data;
 
+        // Buzz controllers when their trigger is pulled
+        for (index, _) in controllers.iter() {
+            if controllers.was_pressed(index, BUTTON_TRIGGER) {
+                haptic_requests.single_write(HapticRequest::new(HapticTarget::Tracker(index), 0.1));
+            }
+        }
+
         for event in xr_events.read(self.xr_event_reader.as_mut().unwrap()) {
             match event {
                 XREvent::TrackerAdded(tracker) => {
@@ -0,0 +1,32 @@
+use amethyst::core::bundle::{Result, SystemBundle};
+use amethyst::core::specs::prelude::DispatcherBuilder;
+use amethyst::xr::XRBundle;
+
+use runtime::Runtime;
+use system::OpenVRSystem;
+use OpenVR;
+
+/// Adds the `XRBundle` for an `OpenVR` backend along with the systems publishing OpenVR specific
+/// data to the world.
+pub struct OpenVRBundle<R: Runtime> {
+    backend: OpenVR<R>,
+}
+
+impl<R: Runtime> OpenVRBundle<R> {
+    pub fn new(backend: OpenVR<R>) -> OpenVRBundle<R> {
+        OpenVRBundle { backend }
+    }
+}
+
+impl<'a, 'b, R: Runtime> SystemBundle<'a, 'b> for OpenVRBundle<R> {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<()> {
+        let system = OpenVRSystem::new(&self.backend);
+
+        XRBundle::new(self.backend).build(builder)?;
+
+        // Runs after the XR system so the data collected during `wait` is published the same frame
+        builder.add(system, "openvr_system", &["xr_system"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::mem;
+
+use openvr_sys as sys;
+
+use runtime::DevicePose;
+
+/// Button ids, for use with `ControllerState` and `OpenVRControllerStates`.
+pub type ButtonId = sys::EVRButtonId;
+
+pub const BUTTON_SYSTEM: ButtonId = sys::EVRButtonId_k_EButton_System;
+pub const BUTTON_APPLICATION_MENU: ButtonId = sys::EVRButtonId_k_EButton_ApplicationMenu;
+pub const BUTTON_GRIP: ButtonId = sys::EVRButtonId_k_EButton_Grip;
+pub const BUTTON_TOUCHPAD: ButtonId = sys::EVRButtonId_k_EButton_SteamVR_Touchpad;
+pub const BUTTON_TRIGGER: ButtonId = sys::EVRButtonId_k_EButton_SteamVR_Trigger;
+
+/// Legacy controller input state as returned by `IVRSystem::GetControllerState`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ControllerState {
+    /// Changes whenever the state changes, so equal packet numbers mean equal states.
+    pub packet_num: u32,
+    pub button_pressed: u64,
+    pub button_touched: u64,
+    /// Up to five axes, their meaning depends on the controller. On most controllers axis 0 is
+    /// the touchpad or thumbstick and axis 1 the trigger, which only uses x.
+    pub axis: [[f32; 2]; 5],
+}
+
+impl ControllerState {
+    pub(crate) fn from_sys(state: &sys::VRControllerState_t) -> ControllerState {
+        let mut axis = [[0.0; 2]; 5];
+        for (axis, sys_axis) in axis.iter_mut().zip(state.rAxis.iter()) {
+            *axis = [sys_axis.x, sys_axis.y];
+        }
+
+        ControllerState {
+            packet_num: state.unPacketNum,
+            button_pressed: state.ulButtonPressed,
+            button_touched: state.ulButtonTouched,
+            axis,
+        }
+    }
+
+    pub(crate) fn to_sys(&self) -> sys::VRControllerState_t {
+        let mut state: sys::VRControllerState_t = unsafe { mem::zeroed() };
+        state.unPacketNum = self.packet_num;
+        state.ulButtonPressed = self.button_pressed;
+        state.ulButtonTouched = self.button_touched;
+        for (sys_axis, axis) in state.rAxis.iter_mut().zip(self.axis.iter()) {
+            sys_axis.x = axis[0];
+            sys_axis.y = axis[1];
+        }
+        state
+    }
+
+    pub fn is_pressed(&self, button: ButtonId) -> bool {
+        self.button_pressed & button_mask(button) != 0
+    }
+
+    pub fn is_touched(&self, button: ButtonId) -> bool {
+        self.button_touched & button_mask(button) != 0
+    }
+}
+
+#[inline]
+fn button_mask(button: ButtonId) -> u64 {
+    1u64 << button as u64
+}
+
+/// Resource holding the legacy controller state of every registered tracker that has one,
+/// keyed by tracker index. Updated every frame by the `OpenVRSystem`.
+#[derive(Default)]
+pub struct OpenVRControllerStates {
+    pub(crate) states: HashMap<u32, ControllerState>,
+    pub(crate) previous_states: HashMap<u32, ControllerState>,
+    pub(crate) poses: HashMap<u32, DevicePose>,
+}
+
+impl OpenVRControllerStates {
+    pub fn get(&self, index: u32) -> Option<&ControllerState> {
+        self.states.get(&index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &ControllerState)> {
+        self.states.iter().map(|(&index, state)| (index, state))
+    }
+
+    /// The pose of a controller at the time its state was sampled, which lines up with button
+    /// presses more closely than the pose of the frame.
+    pub fn pose(&self, index: u32) -> Option<&DevicePose> {
+        self.poses.get(&index)
+    }
+
+    pub fn is_pressed(&self, index: u32, button: ButtonId) -> bool {
+        self.states
+            .get(&index)
+            .map_or(false, |state| state.is_pressed(button))
+    }
+
+    /// Whether the button went down since the previous frame.
+    pub fn was_pressed(&self, index: u32, button: ButtonId) -> bool {
+        let was_down = self
+            .previous_states
+            .get(&index)
+            .map_or(false, |state| state.is_pressed(button));
+
+        !was_down && self.is_pressed(index, button)
+    }
+
+    /// Whether the button went up since the previous frame.
+    pub fn was_released(&self, index: u32, button: ButtonId) -> bool {
+        let was_down = self
+            .previous_states
+            .get(&index)
+            .map_or(false, |state| state.is_pressed(button));
+
+        was_down && !self.is_pressed(index, button)
+    }
+
+    pub fn axis(&self, index: u32, axis: usize) -> Option<[f32; 2]> {
+        self.states
+            .get(&index)
+            .and_then(|state| state.axis.get(axis).cloned())
+    }
+}
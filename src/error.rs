@@ -0,0 +1,82 @@
+use std::error;
+use std::ffi::CStr;
+use std::fmt;
+
+use amethyst::Error;
+use openvr_sys as sys;
+
+/// Errors from initializing the OpenVR backend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpenVRError {
+    /// The runtime or one of its interfaces failed to initialize, with the `EVRInitError` code
+    /// reported by OpenVR.
+    Init(sys::EVRInitError),
+    /// The runtime doesn't provide an interface the backend needs, e.g. because it's too old.
+    InterfaceUnavailable(&'static str),
+    /// There already is an OpenVR context in this process. It has to be shut down before
+    /// another one can be initialized.
+    AlreadyInitialized,
+}
+
+impl OpenVRError {
+    /// The `EVRInitError` code reported by OpenVR, if any.
+    pub fn code(&self) -> Option<sys::EVRInitError> {
+        match *self {
+            OpenVRError::Init(code) => Some(code),
+            OpenVRError::InterfaceUnavailable(_) | OpenVRError::AlreadyInitialized => None,
+        }
+    }
+
+    /// The symbolic name of the error code, e.g. `VRInitError_Init_HmdNotFound`.
+    pub fn symbol(&self) -> Option<String> {
+        self.code().map(|code| unsafe {
+            CStr::from_ptr(sys::VR_GetVRInitErrorAsSymbol(code))
+                .to_string_lossy()
+                .into_owned()
+        })
+    }
+}
+
+impl From<sys::EVRInitError> for OpenVRError {
+    fn from(code: sys::EVRInitError) -> Self {
+        OpenVRError::Init(code)
+    }
+}
+
+impl fmt::Display for OpenVRError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OpenVRError::Init(code) => {
+                let description =
+                    unsafe { CStr::from_ptr(sys::VR_GetVRInitErrorAsEnglishDescription(code)) };
+                write!(
+                    f,
+                    "OpenVR initialization failed: {} ({})",
+                    description.to_string_lossy(),
+                    self.symbol().unwrap_or_default()
+                )
+            }
+            OpenVRError::InterfaceUnavailable(interface) => {
+                write!(f, "OpenVR runtime doesn't provide {}", interface)
+            }
+            OpenVRError::AlreadyInitialized => write!(f, "OpenVR is already initialized"),
+        }
+    }
+}
+
+impl error::Error for OpenVRError {
+    fn description(&self) -> &str {
+        match *self {
+            OpenVRError::Init(_) => "OpenVR initialization failed",
+            OpenVRError::InterfaceUnavailable(_) => "OpenVR interface unavailable",
+            OpenVRError::AlreadyInitialized => "OpenVR already initialized",
+        }
+    }
+}
+
+/// amethyst's error can't carry the details, so log the error before converting it.
+impl From<OpenVRError> for Error {
+    fn from(_: OpenVRError) -> Self {
+        Error::Application
+    }
+}
@@ -0,0 +1,85 @@
+use openvr::system::event::{Event, EventInfo};
+
+use properties::ControllerRole;
+
+/// Events reported by the OpenVR runtime, published on an `EventChannel<OpenVREvent>` by the
+/// `OpenVRSystem`.
+///
+/// Trackers are identified by the same index used by the `XRBackend` tracker API.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OpenVREvent {
+    TrackerActivated(u32),
+    TrackerDeactivated(u32),
+    /// One of the tracker's properties changed.
+    TrackerUpdated(u32),
+    /// The controller roles changed, for example because the user swapped hands. Followed by a
+    /// `ControllerRoleChanged` for every registered tracker whose role is now different.
+    TrackerRoleChanged,
+    ControllerRoleChanged { tracker: u32, role: ControllerRole },
+    ButtonPressed { tracker: u32, button: u32 },
+    ButtonReleased { tracker: u32, button: u32 },
+    ButtonTouched { tracker: u32, button: u32 },
+    ButtonUntouched { tracker: u32, button: u32 },
+    /// The runtime wants the application to quit. The backend has already acknowledged the
+    /// request and disconnects, so the application should exit soon after. If it keeps running,
+    /// it stays disconnected until `OpenVR::restart` is called.
+    QuitRequested,
+    DashboardActivated,
+    DashboardDeactivated,
+    /// The play area or collision bounds changed, `XRBackend::get_area` should be queried again.
+    ChaperoneChanged,
+    /// The user adjusted the interpupillary distance of the HMD, in meters.
+    IpdChanged(f32),
+    /// The seated zero pose was recentered, by the application or the user. The new transform is
+    /// available from `OpenVRTracking::seated_to_standing`.
+    SeatedZeroPoseReset,
+    /// The runtime stopped responding, e.g. because SteamVR crashed, or it asked the application
+    /// to quit. All trackers are removed. After a crash the backend tries to reconnect every few
+    /// seconds, after `QuitRequested` only once `OpenVR::restart` is called.
+    RuntimeDisconnected,
+    /// The backend reconnected to the runtime after `RuntimeDisconnected`. Trackers are added
+    /// again as their poses come in.
+    RuntimeReconnected,
+}
+
+impl OpenVREvent {
+    /// Translates a runtime event, returning `None` for events that aren't of interest.
+    pub(crate) fn from_event_info(info: &EventInfo) -> Option<OpenVREvent> {
+        let tracker = info.tracked_device_index;
+
+        Some(match info.event {
+            Event::TrackedDeviceActivated => OpenVREvent::TrackerActivated(tracker),
+            Event::TrackedDeviceDeactivated => OpenVREvent::TrackerDeactivated(tracker),
+            Event::TrackedDeviceUpdated => OpenVREvent::TrackerUpdated(tracker),
+            Event::TrackedDeviceRoleChanged => OpenVREvent::TrackerRoleChanged,
+            Event::ButtonPress(ref c) => OpenVREvent::ButtonPressed {
+                tracker,
+                button: c.button,
+            },
+            Event::ButtonUnpress(ref c) => OpenVREvent::ButtonReleased {
+                tracker,
+                button: c.button,
+            },
+            Event::ButtonTouch(ref c) => OpenVREvent::ButtonTouched {
+                tracker,
+                button: c.button,
+            },
+            Event::ButtonUntouch(ref c) => OpenVREvent::ButtonUntouched {
+                tracker,
+                button: c.button,
+            },
+            Event::Quit(_) => OpenVREvent::QuitRequested,
+            Event::ProcessQuit(ref process) if process.pid == ::std::process::id() => {
+                OpenVREvent::QuitRequested
+            }
+            Event::DashboardActivated => OpenVREvent::DashboardActivated,
+            Event::DashboardDeactivated => OpenVREvent::DashboardDeactivated,
+            Event::ChaperoneDataHasChanged | Event::ChaperoneSettingsHaveChanged => {
+                OpenVREvent::ChaperoneChanged
+            }
+            Event::IpdChanged(ref ipd) => OpenVREvent::IpdChanged(ipd.ipd_meters),
+            Event::SeatedZeroPoseReset(_) => OpenVREvent::SeatedZeroPoseReset,
+            _ => return None,
+        })
+    }
+}
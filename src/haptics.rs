@@ -0,0 +1,92 @@
+use std::time::{Duration, Instant};
+
+use amethyst::core::specs::prelude::{Component, DenseVecStorage};
+
+/// The longest pulse the legacy haptics API accepts, in microseconds.
+const MAX_PULSE_MICROS: f32 = 3999.0;
+
+/// What a `HapticRequest` should vibrate.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HapticTarget {
+    /// A tracker by index, using the legacy `TriggerHapticPulse` API. It has no notion of
+    /// frequency, and amplitude is approximated by the length of the pulses sent each frame.
+    Tracker(u32),
+    /// A SteamVR Input vibration action registered with `OpenVR::add_action`.
+    Action(String),
+}
+
+/// Requests a vibration. Write these to the `EventChannel<HapticRequest>` resource, they are
+/// dispatched during the next `wait`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HapticRequest {
+    pub target: HapticTarget,
+    /// In seconds.
+    pub duration: f32,
+    /// In hertz.
+    pub frequency: f32,
+    /// Between 0 and 1.
+    pub amplitude: f32,
+}
+
+impl HapticRequest {
+    pub fn new(target: HapticTarget, duration: f32) -> HapticRequest {
+        HapticRequest {
+            target,
+            duration,
+            frequency: 160.0,
+            amplitude: 1.0,
+        }
+    }
+}
+
+/// Component for vibrating the tracker an entity represents, e.g. from a system handling
+/// collisions of controller entities. Requests are taken by the `OpenVRSystem` every frame and
+/// dispatched during the next `wait`.
+pub struct HapticFeedback {
+    tracker: u32,
+    pub(crate) requests: Vec<HapticRequest>,
+}
+
+impl Component for HapticFeedback {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl HapticFeedback {
+    pub fn new(tracker: u32) -> HapticFeedback {
+        HapticFeedback {
+            tracker,
+            requests: Vec::new(),
+        }
+    }
+
+    /// The index of the tracker this vibrates.
+    pub fn tracker(&self) -> u32 {
+        self.tracker
+    }
+
+    /// Vibrates the tracker for `duration` seconds, with the amplitude between 0 and 1.
+    pub fn pulse(&mut self, duration: f32, amplitude: f32) {
+        self.requests.push(HapticRequest {
+            amplitude,
+            ..HapticRequest::new(HapticTarget::Tracker(self.tracker), duration)
+        });
+    }
+}
+
+/// A legacy pulse which has to be repeated every frame until its duration has passed.
+pub(crate) struct LegacyPulse {
+    pub tracker: u32,
+    pub pulse_micros: u16,
+    pub end: Instant,
+}
+
+impl LegacyPulse {
+    pub fn new(tracker: u32, duration: f32, amplitude: f32) -> LegacyPulse {
+        let duration_nanos = (duration.max(0.0) * 1_000_000_000.0) as u64;
+        LegacyPulse {
+            tracker,
+            pulse_micros: (amplitude.max(0.0).min(1.0) * MAX_PULSE_MICROS) as u16,
+            end: Instant::now() + Duration::from_nanos(duration_nanos),
+        }
+    }
+}
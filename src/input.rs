@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use std::ffi::CString;
+use std::result::Result as StdResult;
+
+use openvr_sys as sys;
+
+use runtime::{DevicePose, Runtime};
+
+pub type ActionSetHandle = sys::VRActionSetHandle_t;
+pub type ActionHandle = sys::VRActionHandle_t;
+
+/// Number of bones in the SteamVR hand skeleton.
+pub const HAND_BONE_COUNT: usize = 31;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputError {
+    /// The runtime doesn't provide the `IVRInput` interface.
+    Unavailable,
+    /// The action manifest path can't be passed to the runtime.
+    InvalidPath,
+    /// The action or action set isn't registered with the backend, or its name can't be passed to
+    /// the runtime.
+    UnknownAction,
+    /// The backend is disconnected from the runtime.
+    Disconnected,
+    Runtime(sys::EVRInputError),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InputError::Unavailable => write!(f, "SteamVR Input isn't available"),
+            InputError::InvalidPath => write!(f, "Invalid action manifest path"),
+            InputError::UnknownAction => write!(f, "Unknown action"),
+            InputError::Disconnected => write!(f, "Not connected to the OpenVR runtime"),
+            InputError::Runtime(error) => write!(f, "SteamVR Input error {}", error),
+        }
+    }
+}
+
+/// The kind of data an action in the action manifest provides.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActionKind {
+    Digital,
+    Analog,
+    Pose,
+    Skeletal,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DigitalActionData {
+    /// Whether the action is bound to a connected device and its action set is active.
+    pub active: bool,
+    pub state: bool,
+    /// Whether `state` changed since the previous frame.
+    pub changed: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AnalogActionData {
+    pub active: bool,
+    pub value: [f32; 3],
+    /// Change of `value` since the previous frame.
+    pub delta: [f32; 3],
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PoseActionData {
+    pub active: bool,
+    pub pose: DevicePose,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BoneTransform {
+    pub position: [f32; 3],
+    /// Quaternion in `[w, x, y, z]` order.
+    pub orientation: [f32; 4],
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SkeletalActionData {
+    pub active: bool,
+    /// Bone transforms relative to their parent bone, `HAND_BONE_COUNT` of them when active.
+    pub bones: Vec<BoneTransform>,
+}
+
+#[derive(Clone, Debug)]
+pub enum ActionState {
+    Digital(DigitalActionData),
+    Analog(AnalogActionData),
+    Pose(PoseActionData),
+    Skeletal(SkeletalActionData),
+}
+
+/// The action sets and actions registered with the backend.
+#[derive(Default)]
+pub(crate) struct ActionRegistry {
+    pub sets: Vec<(String, ActionSetHandle)>,
+    pub active_sets: Vec<ActionSetHandle>,
+    pub actions: Vec<(String, ActionHandle, ActionKind)>,
+}
+
+impl ActionRegistry {
+    pub fn activate(&mut self, names: &[String]) {
+        self.active_sets = self
+            .sets
+            .iter()
+            .filter(|(name, _)| names.contains(name))
+            .map(|&(_, handle)| handle)
+            .collect();
+    }
+
+    /// Looks every handle up again, as they aren't valid across runtime sessions.
+    pub fn resolve_handles<R: Runtime>(&mut self, runtime: &R) -> StdResult<(), InputError> {
+        let active_sets: Vec<String> = self
+            .sets
+            .iter()
+            .filter(|&&(_, handle)| self.active_sets.contains(&handle))
+            .map(|&(ref name, _)| name.clone())
+            .collect();
+
+        for &mut (ref name, ref mut handle) in &mut self.sets {
+            *handle = runtime.action_set_handle(&c_name(name)?)?;
+        }
+        for &mut (ref name, ref mut handle, _) in &mut self.actions {
+            *handle = runtime.action_handle(&c_name(name)?)?;
+        }
+
+        self.activate(&active_sets);
+        Ok(())
+    }
+}
+
+fn c_name(name: &str) -> StdResult<CString, InputError> {
+    CString::new(name).map_err(|_| InputError::UnknownAction)
+}
+
+/// Resource holding the state of every registered SteamVR Input action as of the last frame,
+/// keyed by action path, e.g. `/actions/main/in/trigger`. Kept up to date by the
+/// `OpenVRSystem`.
+#[derive(Default)]
+pub struct OpenVRInput {
+    pub(crate) actions: HashMap<String, ActionState>,
+    pub(crate) requested_action_sets: Option<Vec<String>>,
+}
+
+impl OpenVRInput {
+    pub fn action(&self, name: &str) -> Option<&ActionState> {
+        self.actions.get(name)
+    }
+
+    /// Whether a digital action is active and pressed.
+    pub fn action_is_down(&self, name: &str) -> Option<bool> {
+        match self.actions.get(name) {
+            Some(ActionState::Digital(data)) => Some(data.active && data.state),
+            _ => None,
+        }
+    }
+
+    /// Whether a digital action changed state this frame.
+    pub fn action_changed(&self, name: &str) -> Option<bool> {
+        match self.actions.get(name) {
+            Some(ActionState::Digital(data)) => Some(data.active && data.changed),
+            _ => None,
+        }
+    }
+
+    /// The value of an analog action. Unused axes are 0, and all of them are when the action
+    /// isn't active.
+    pub fn axis_value(&self, name: &str) -> Option<[f32; 3]> {
+        match self.actions.get(name) {
+            Some(ActionState::Analog(data)) if data.active => Some(data.value),
+            Some(ActionState::Analog(_)) => Some([0.0; 3]),
+            _ => None,
+        }
+    }
+
+    pub fn pose(&self, name: &str) -> Option<&DevicePose> {
+        match self.actions.get(name) {
+            Some(ActionState::Pose(data)) if data.active => Some(&data.pose),
+            _ => None,
+        }
+    }
+
+    pub fn bones(&self, name: &str) -> Option<&[BoneTransform]> {
+        match self.actions.get(name) {
+            Some(ActionState::Skeletal(data)) if data.active => Some(&data.bones),
+            _ => None,
+        }
+    }
+
+    /// Replaces the action sets updated each frame, by name. Applied during the next `wait`.
+    pub fn set_active_action_sets(&mut self, names: Vec<String>) {
+        self.requested_action_sets = Some(names);
+    }
+}
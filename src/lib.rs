@@ -4,166 +4,771 @@ extern crate amethyst;
 extern crate openvr;
 extern crate openvr_sys;
 
-pub use openvr::ApplicationType;
+mod bundle;
+mod controller;
+mod error;
+mod event;
+mod haptics;
+mod input;
+mod properties;
+mod render_models;
+pub mod runtime;
+mod shared;
+mod system;
+#[cfg(test)]
+mod tests;
+mod tracking;
 
-use std::ffi::CStr;
+pub use bundle::OpenVRBundle;
+pub use controller::{
+    ButtonId, ControllerState, OpenVRControllerStates, BUTTON_APPLICATION_MENU, BUTTON_GRIP,
+    BUTTON_SYSTEM, BUTTON_TOUCHPAD, BUTTON_TRIGGER,
+};
+pub use error::OpenVRError;
+pub use event::OpenVREvent;
+pub use haptics::{HapticFeedback, HapticRequest, HapticTarget};
+pub use input::{
+    ActionKind, ActionState, AnalogActionData, BoneTransform, DigitalActionData, InputError,
+    OpenVRInput, PoseActionData, SkeletalActionData, HAND_BONE_COUNT,
+};
+pub use openvr::system::HiddenAreaMeshType;
+pub use openvr::{ApplicationType, Eye, TrackingUniverseOrigin};
+pub use properties::{ControllerRole, OpenVRTrackers, TrackerProperties};
+pub use render_models::{ComponentMaterial, RenderModelProgress};
+pub use runtime::TrackingResult;
+pub use system::{OpenVRActionEventSystem, OpenVRSystem};
+pub use tracking::{OpenVRTracking, PoseKind, TrackerComponentState};
+
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
 use std::result::Result as StdResult;
 
-use amethyst::core::cgmath::{Matrix4, Quaternion, Vector3};
-use amethyst::{Error, Result};
+use amethyst::core::cgmath::{InnerSpace, Matrix4, Quaternion, Vector3};
 
 use amethyst::xr::{
-    TrackerCapabilities, TrackerComponentModelInfo, TrackerComponentTextureData,
-    TrackerComponentVertex, TrackerModelLoadStatus, TrackerPositionData, XRBackend, XRTargetInfo,
-};
-use openvr::compositor::texture::{ColorSpace, Handle, Texture};
-use openvr::render_models::Error as RenderModelError;
-use openvr::{
-    init, Compositor, Context, Eye, RenderModels, System, TrackedDeviceClass, TrackedDevicePoses,
-    TrackingUniverseOrigin,
+    TrackerCapabilities, TrackerComponentVertex, TrackerModelLoadStatus, TrackerPositionData,
+    XRBackend, XRTargetInfo,
 };
+use openvr::TrackedDeviceClass;
+use haptics::LegacyPulse;
+use input::ActionRegistry;
+use render_models::RenderModelLoader;
+use runtime::{ComponentState, DevicePose, OpenVRRuntime, Runtime};
+use shared::Shared;
+use tracking::inverse_transform_point;
+
+/// How long compositor waits have to keep failing before the runtime is considered lost.
+const RUNTIME_LOST_AFTER_SECS: u64 = 5;
+/// How often to try reconnecting to a lost runtime.
+const RECONNECT_INTERVAL_SECS: u64 = 2;
+/// How long `wait` sleeps while disconnected or when the compositor fails to wait, standing in
+/// for the compositor's wait for the next frame so the game loop doesn't spin.
+const DISCONNECTED_FRAME_MILLIS: u64 = 11;
+
+/// OpenVR backend for amethyst's XR support. Generic over the runtime so that it can be driven
+/// by a `runtime::mock::MockRuntime` when there is no headset around.
+pub struct OpenVR<R: Runtime = OpenVRRuntime> {
+    runtime: R,
+    shared: Shared,
+
+    tracking_origin: TrackingUniverseOrigin,
+    seated_to_standing: [[f32; 4]; 3],
+
+    tracked_device_poses: Option<Vec<DevicePose>>,
+    tracked_device_game_poses: Option<Vec<DevicePose>>,
+    tracker_pose_kind: PoseKind,
+
+    registered_trackers: Option<Vec<bool>>,
+    /// Render model components of registered trackers, queried once per tracker.
+    tracker_components: HashMap<u32, TrackerComponents>,
 
-pub struct OpenVR {
-    _context: Context,
-    system: System,
-    compositor: Compositor,
-    render_models: RenderModels,
+    quitting: bool,
 
-    tracked_device_poses: Option<TrackedDevicePoses>,
+    /// When compositor waits started failing, if the last one did.
+    failing_since: Option<Instant>,
+    /// When to try reconnecting next, while the runtime is lost.
+    next_reconnect: Option<Instant>,
 
-    registered_trackers: Option<[bool; 16]>,
+    actions: ActionRegistry,
+
+    legacy_pulses: Vec<LegacyPulse>,
+
+    render_models: RenderModelLoader,
+
+    /// What `get_gl_target_info` was last built from, so it can still answer while disconnected.
+    eye_targets: Option<EyeTargets>,
+}
+
+/// The render model of a tracker and its components.
+struct TrackerComponents {
+    model_name: CString,
+    /// The name of each component, along with its state once it turned out to be static.
+    components: Vec<(CString, Option<ComponentState>)>,
+}
+
+/// The render target size along with the eye-to-head transform and projection of each eye.
+#[derive(Clone, Copy)]
+struct EyeTargets {
+    size: (u32, u32),
+    eye_to_head: [[[f32; 4]; 3]; 2],
+    projection: [[[f32; 4]; 4]; 2],
 }
 
-impl OpenVR {
+impl OpenVR<OpenVRRuntime> {
     pub fn is_available() -> bool {
         unsafe { openvr_sys::VR_IsHmdPresent() }
     }
 
-    pub fn init(application_type: ApplicationType) -> Result<OpenVR> {
-        // TODO: Handle unsafe
-        let context = unsafe { init(application_type).map_err(|_| Error::Application)? };
-        let system = context.system().map_err(|_| Error::Application)?;
-        let compositor = context.compositor().map_err(|_| Error::Application)?;
-        let render_models = context.render_models().map_err(|_| Error::Application)?;
+    /// Initializes OpenVR using the standing tracking universe.
+    pub fn init(application_type: ApplicationType) -> StdResult<OpenVR, OpenVRError> {
+        OpenVR::init_with_origin(application_type, TrackingUniverseOrigin::Standing)
+    }
 
-        Ok(OpenVR {
-            _context: context,
-            system,
-            compositor,
-            render_models,
+    /// Initializes OpenVR with poses and the play area reported relative to `origin`. Seated
+    /// experiences should use `TrackingUniverseOrigin::Seated`.
+    pub fn init_with_origin(
+        application_type: ApplicationType,
+        origin: TrackingUniverseOrigin,
+    ) -> StdResult<OpenVR, OpenVRError> {
+        let mut openvr = OpenVR::with_runtime(OpenVRRuntime::init(application_type)?);
+        openvr.set_tracking_origin(origin);
+        Ok(openvr)
+    }
+}
+
+impl<R: Runtime> OpenVR<R> {
+    pub fn with_runtime(runtime: R) -> OpenVR<R> {
+        let mut openvr = OpenVR {
+            runtime,
+            shared: Shared::default(),
+
+            tracking_origin: TrackingUniverseOrigin::Standing,
+            seated_to_standing: DevicePose::default().device_to_absolute_tracking,
 
             tracked_device_poses: None,
+            tracked_device_game_poses: None,
+            tracker_pose_kind: PoseKind::Render,
 
             registered_trackers: None,
-        })
+            tracker_components: HashMap::new(),
+
+            quitting: false,
+
+            failing_since: None,
+            next_reconnect: None,
+
+            actions: ActionRegistry::default(),
+
+            legacy_pulses: Vec::new(),
+
+            render_models: RenderModelLoader::default(),
+
+            eye_targets: None,
+        };
+
+        openvr.update_seated_zero_pose();
+        openvr
+    }
+
+    /// Whether the runtime asked the application to quit. The backend disconnects once it has,
+    /// and stays disconnected until `restart` is called, which clears this again.
+    pub fn is_quitting(&self) -> bool {
+        self.quitting
+    }
+
+    /// Shuts the runtime down. Dropping the backend does the same, this only makes it explicit.
+    pub fn shutdown(mut self) {
+        self.runtime.shutdown();
+    }
+
+    /// Shuts the runtime down and connects to it again, e.g. after SteamVR was restarted. The
+    /// tracking origin carries over, and poses are invalid until the next `wait`. If connecting
+    /// fails, the backend stays disconnected and `wait` keeps trying to reconnect, also after the
+    /// runtime asked the application to quit.
+    pub fn restart(&mut self) -> StdResult<(), OpenVRError> {
+        self.disconnect();
+        self.quitting = false;
+
+        self.runtime.reinit()?;
+        self.next_reconnect = None;
+        self.render_models = RenderModelLoader::default();
+        let origin = self.tracking_origin;
+        self.set_tracking_origin(origin);
+        self.update_seated_zero_pose();
+        if let Err(e) = self.actions.resolve_handles(&self.runtime) {
+            warn!("Failed to restore SteamVR Input actions: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Whether the backend is connected to the runtime. It disconnects when the runtime stops
+    /// responding, e.g. because SteamVR crashed, and tries to reconnect every few seconds during
+    /// `wait`. It also disconnects when the runtime asks the application to quit, but then only
+    /// reconnects on `restart`. While disconnected all trackers are reported as removed and
+    /// nothing is submitted.
+    pub fn is_connected(&self) -> bool {
+        self.runtime.is_initialized()
+    }
+
+    fn disconnect(&mut self) {
+        // Every tracker is reported as removed, and added again once poses come in after
+        // reconnecting, as the runtime might assign different indices by then
+        let device_count = self.tracked_device_poses.as_ref().map_or(0, |poses| poses.len());
+        let disconnected = vec![DevicePose::default(); device_count];
+        self.runtime.shutdown();
+
+        self.tracked_device_poses = Some(disconnected.clone());
+        self.tracked_device_game_poses = Some(disconnected.clone());
+        self.failing_since = None;
+        self.legacy_pulses.clear();
+        self.tracker_components.clear();
+
+        let mut shared = self.shared.lock();
+        shared.game_poses = disconnected;
+        shared.seconds_to_photons = 0.0;
+        shared.predicted_poses.clear();
+        shared.tracking_results.clear();
+        shared.controller_states.clear();
+        shared.controller_poses.clear();
+        shared.action_states.clear();
+        shared.component_states.clear();
+        shared.haptic_requests.clear();
+    }
+
+    /// Disconnects after losing the runtime, trying to reconnect from the next interval on.
+    fn lose_runtime(&mut self) {
+        self.disconnect();
+        self.next_reconnect = Some(Instant::now() + Duration::from_secs(RECONNECT_INTERVAL_SECS));
+        self.shared.lock().push_event(OpenVREvent::RuntimeDisconnected);
+    }
+
+    fn try_reconnect(&mut self) {
+        let now = Instant::now();
+        if self.next_reconnect.map_or(false, |at| now < at) {
+            return;
+        }
+        self.next_reconnect = Some(now + Duration::from_secs(RECONNECT_INTERVAL_SECS));
+
+        match self.restart() {
+            Ok(()) => {
+                info!("Reconnected to the OpenVR runtime");
+                self.shared.lock().push_event(OpenVREvent::RuntimeReconnected);
+            }
+            Err(e) => debug!("Failed to reconnect to the OpenVR runtime: {}", e),
+        }
+    }
+
+    pub fn tracking_origin(&self) -> TrackingUniverseOrigin {
+        self.tracking_origin
+    }
+
+    /// Changes the origin poses, events and the play area are reported relative to, starting
+    /// with the next frame. While disconnected it's applied once the backend reconnects.
+    pub fn set_tracking_origin(&mut self, origin: TrackingUniverseOrigin) {
+        if self.is_connected() {
+            self.runtime.set_tracking_space(origin);
+        }
+        self.tracking_origin = origin;
+        self.shared.lock().tracking_origin = Some(origin);
+    }
+
+    /// The position of a tracker as predicted by the compositor for one frame after the current
+    /// one, which is what gameplay logic should use. Also available to systems through
+    /// `OpenVRTracking::game_tracker_position`.
+    pub fn get_game_tracker_position(&self, index: u32) -> TrackerPositionData {
+        self.get_tracker_position_of_kind(index, PoseKind::Game)
+    }
+
+    pub fn get_tracker_position_of_kind(&self, index: u32, kind: PoseKind) -> TrackerPositionData {
+        position_data(
+            self.poses_of_kind(kind)
+                .and_then(|poses| poses.get(index as usize)),
+        )
+    }
+
+    /// How well a tracker is tracked this frame, according to the poses selected by
+    /// `set_tracker_pose_kind`. `TrackerPositionData::valid` only tells whether there is a pose
+    /// at all, this also tells whether it's reliable. Also available to systems through
+    /// `OpenVRTracking::tracking_result`.
+    pub fn get_tracking_result(&self, index: u32) -> TrackingResult {
+        self.poses_of_kind(self.tracker_pose_kind)
+            .and_then(|poses| poses.get(index as usize))
+            .map_or(TrackingResult::Uninitialized, |pose| pose.tracking_result)
+    }
+
+    fn poses_of_kind(&self, kind: PoseKind) -> Option<&[DevicePose]> {
+        let poses = match kind {
+            PoseKind::Render => &self.tracked_device_poses,
+            PoseKind::Game => &self.tracked_device_game_poses,
+        };
+        poses.as_ref().map(|poses| poses.as_slice())
     }
 
-    fn load_model(
+    /// Selects the poses `XRBackend::get_tracker_position`, and with it the transforms of
+    /// tracker entities, are based on. Defaults to `PoseKind::Render`.
+    pub fn set_tracker_pose_kind(&mut self, kind: PoseKind) {
+        self.tracker_pose_kind = kind;
+    }
+
+    /// Seconds from now until a frame submitted right now would be shown on the HMD, based on
+    /// the vsync timing of the display. 0 while disconnected. Also available to systems, as of
+    /// the last `wait`, through `OpenVRTracking::seconds_to_photons`.
+    pub fn seconds_to_photons(&self) -> f32 {
+        if !self.is_connected() {
+            return 0.0;
+        }
+
+        let hmd = openvr_sys::k_unTrackedDeviceIndex_Hmd;
+        let frame_duration = self
+            .runtime
+            .float_tracked_device_property(
+                hmd,
+                openvr_sys::ETrackedDeviceProperty_Prop_DisplayFrequency_Float,
+            ).filter(|&frequency| frequency > 0.0)
+            .map_or(1.0 / 90.0, |frequency| 1.0 / frequency);
+        let vsync_to_photons = self
+            .runtime
+            .float_tracked_device_property(
+                hmd,
+                openvr_sys::ETrackedDeviceProperty_Prop_SecondsFromVsyncToPhotons_Float,
+            ).unwrap_or(0.0);
+        let since_vsync = self.runtime.time_since_last_vsync().unwrap_or(0.0);
+
+        (frame_duration - since_vsync).max(0.0) + vsync_to_photons
+    }
+
+    /// Positions of all trackers, indexed like the tracker API, predicted `seconds_from_now`
+    /// seconds ahead. Useful for systems running at a different rate than rendering, like
+    /// physics or audio. Add `seconds_to_photons` to predict relative to the next frame being
+    /// displayed instead. Empty while disconnected. Systems can request these every frame
+    /// through `OpenVRTracking::set_predicted_seconds`.
+    pub fn get_predicted_tracker_positions(
         &self,
-        model_name: &CStr,
-    ) -> StdResult<Option<TrackerComponentModelInfo>, RenderModelError> {
-        if let Some(model) = self.render_models.load_render_model(&model_name)? {
-            if let Some(texture_id) = model.diffuse_texture_id() {
-                if let Ok(maybe_texture) = self.render_models.load_texture(texture_id) {
-                    if let Some(texture) = maybe_texture {
-                        let vertices = convert_vertices(model.vertices());
-                        let indices = model.indices().to_vec();
-
-                        let texture = TrackerComponentTextureData {
-                            data: texture.data().to_vec(),
-                            size: texture.dimensions(),
-                        };
-
-                        Ok(Some(TrackerComponentModelInfo {
-                            component_name: model_name.to_str().ok().map(String::from),
-                            vertices,
-                            indices,
-                            texture: Some(texture),
-                        }))
-                    } else {
-                        Ok(None)
+        seconds_from_now: f32,
+    ) -> Vec<TrackerPositionData> {
+        if !self.is_connected() {
+            return Vec::new();
+        }
+
+        self.runtime
+            .device_to_absolute_tracking_pose(self.tracking_origin, seconds_from_now)
+            .iter()
+            .map(|pose| position_data(Some(pose)))
+            .collect()
+    }
+
+    /// Publishes the frame timing and the predicted poses requested through `OpenVRTracking`.
+    fn update_prediction(&mut self) {
+        let seconds_to_photons = self.seconds_to_photons();
+        let predicted_seconds = self.shared.lock().predicted_seconds;
+        let predicted_poses = predicted_seconds.map_or_else(Vec::new, |seconds| {
+            self.runtime
+                .device_to_absolute_tracking_pose(self.tracking_origin, seconds)
+        });
+
+        let mut shared = self.shared.lock();
+        shared.seconds_to_photons = seconds_to_photons;
+        shared.predicted_poses = predicted_poses;
+    }
+
+    /// Recenters the seated zero pose on the current HMD pose. The runtime follows up with a
+    /// `OpenVREvent::SeatedZeroPoseReset`, like when the user recenters. Does nothing while
+    /// disconnected.
+    pub fn reset_seated_zero_pose(&mut self) {
+        if !self.is_connected() {
+            return;
+        }
+
+        self.runtime.reset_seated_zero_pose();
+        self.update_seated_zero_pose();
+    }
+
+    /// Transform from the seated to the standing tracking space, as last known while connected.
+    pub fn seated_zero_pose_to_standing(&self) -> Matrix4<f32> {
+        array_to_matrix(extend_matrix_array(self.seated_to_standing))
+    }
+
+    fn update_seated_zero_pose(&mut self) {
+        self.seated_to_standing = self.runtime.seated_zero_pose_to_standing();
+        self.shared.lock().seated_to_standing = Some(self.seated_to_standing);
+    }
+
+    /// Loads a SteamVR Input action manifest. The path should be absolute.
+    pub fn load_action_manifest<P: AsRef<Path>>(&mut self, path: P) -> StdResult<(), InputError> {
+        if !self.is_connected() {
+            return Err(InputError::Disconnected);
+        }
+
+        let path = path
+            .as_ref()
+            .to_str()
+            .and_then(|path| CString::new(path).ok())
+            .ok_or(InputError::InvalidPath)?;
+
+        self.runtime.set_action_manifest_path(&path)
+    }
+
+    /// Registers an action set from the action manifest, e.g. `/actions/main`. Registered sets
+    /// are active until `OpenVRInput::set_active_action_sets` says otherwise.
+    pub fn add_action_set(&mut self, name: &str) -> StdResult<(), InputError> {
+        if !self.is_connected() {
+            return Err(InputError::Disconnected);
+        }
+
+        let c_name = CString::new(name).map_err(|_| InputError::UnknownAction)?;
+        let handle = self.runtime.action_set_handle(&c_name)?;
+
+        self.actions.sets.push((name.to_owned(), handle));
+        self.actions.active_sets.push(handle);
+        Ok(())
+    }
+
+    /// Registers an action from the action manifest, e.g. `/actions/main/in/trigger`. Its state
+    /// is read every `wait` and published through the `OpenVRInput` resource.
+    pub fn add_action(&mut self, name: &str, kind: ActionKind) -> StdResult<(), InputError> {
+        if !self.is_connected() {
+            return Err(InputError::Disconnected);
+        }
+
+        let c_name = CString::new(name).map_err(|_| InputError::UnknownAction)?;
+        let handle = self.runtime.action_handle(&c_name)?;
+
+        self.actions.actions.push((name.to_owned(), handle, kind));
+        Ok(())
+    }
+
+    /// Polls the legacy controller state of a tracker, `None` if it isn't a controller or the
+    /// backend is disconnected.
+    pub fn get_controller_state(&self, index: u32) -> Option<ControllerState> {
+        if !self.is_connected() {
+            return None;
+        }
+
+        self.runtime.controller_state(index)
+    }
+
+    fn update_controller_states(&self) {
+        let registered_trackers = match self.registered_trackers {
+            Some(ref trackers) => trackers,
+            None => return,
+        };
+
+        let mut states = HashMap::new();
+        let mut poses = HashMap::new();
+        for (index, _) in registered_trackers
+            .iter()
+            .enumerate()
+            .filter(|&(_, &registered)| registered)
+        {
+            let index = index as u32;
+            if let Some((state, pose)) = self
+                .runtime
+                .controller_state_with_pose(index, self.tracking_origin)
+            {
+                states.insert(index, state);
+                poses.insert(index, pose);
+            }
+        }
+
+        let mut shared = self.shared.lock();
+        shared.controller_states = states;
+        shared.controller_poses = poses;
+    }
+
+    /// The state of each render model component of a tracker as of the last `wait`. Also
+    /// available to systems through `OpenVRTracking::component_states`.
+    pub fn get_component_states(&self, index: u32) -> Vec<TrackerComponentState> {
+        self.shared
+            .lock()
+            .component_states
+            .get(&index)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn update_component_states(&mut self) {
+        let registered_trackers = match self.registered_trackers {
+            Some(ref trackers) => trackers,
+            None => return,
+        };
+
+        // Polled by `update_controller_states` just before
+        let controller_states = self.shared.lock().controller_states.clone();
+
+        let mut states = HashMap::new();
+        for (index, _) in registered_trackers
+            .iter()
+            .enumerate()
+            .filter(|&(_, &registered)| registered)
+        {
+            let index = index as u32;
+            // Components follow the same pose as the tracker itself
+            let device_transform = array_to_matrix(extend_matrix_array(
+                self.poses_of_kind(self.tracker_pose_kind)
+                    .and_then(|poses| poses.get(index as usize))
+                    .cloned()
+                    .unwrap_or_default()
+                    .device_to_absolute_tracking,
+            ));
+
+            let runtime = &self.runtime;
+            let tracker = self
+                .tracker_components
+                .entry(index)
+                .or_insert_with(|| query_components(runtime, index));
+            if tracker.components.is_empty() {
+                continue;
+            }
+
+            let model_name = &tracker.model_name;
+            let controller_state = controller_states.get(&index);
+            let component_states = tracker
+                .components
+                .iter_mut()
+                .filter_map(|&mut (ref component_name, ref mut static_state)| {
+                    let state = match *static_state {
+                        Some(state) => state,
+                        None => {
+                            let state = runtime.component_state(
+                                index,
+                                model_name,
+                                component_name,
+                                controller_state,
+                            )?;
+                            if state.is_static {
+                                *static_state = Some(state);
+                            }
+                            state
+                        }
+                    };
+                    let local_transform = array_to_matrix(extend_matrix_array(state.transform));
+
+                    Some(TrackerComponentState {
+                        name: component_name.to_string_lossy().into_owned(),
+                        local_transform,
+                        tracking_transform: device_transform * local_transform,
+                        visible: state.is_visible,
+                        touched: state.is_touched,
+                        pressed: state.is_pressed,
+                        scrolled: state.is_scrolled,
+                    })
+                }).collect();
+            states.insert(index, component_states);
+        }
+
+        self.shared.lock().component_states = states;
+    }
+
+    /// Vibrates a tracker through the legacy haptics API. Pulses longer than a frame are repeated
+    /// every `wait` until `duration` seconds have passed, with the amplitude between 0 and 1
+    /// controlling the length of each pulse. Does nothing while disconnected.
+    pub fn trigger_haptic_pulse(&mut self, index: u32, duration: f32, amplitude: f32) {
+        if !self.is_connected() {
+            return;
+        }
+
+        let pulse = LegacyPulse::new(index, duration, amplitude);
+        self.runtime
+            .trigger_haptic_pulse(pulse.tracker, 0, pulse.pulse_micros);
+        self.legacy_pulses.push(pulse);
+    }
+
+    /// Triggers a vibration action registered with `add_action`, with the duration in seconds,
+    /// frequency in hertz and amplitude between 0 and 1.
+    pub fn trigger_haptic_vibration(
+        &mut self,
+        action: &str,
+        duration: f32,
+        frequency: f32,
+        amplitude: f32,
+    ) -> StdResult<(), InputError> {
+        if !self.is_connected() {
+            return Err(InputError::Disconnected);
+        }
+
+        let handle = self
+            .actions
+            .actions
+            .iter()
+            .find(|&&(ref name, _, _)| name == action)
+            .map(|&(_, handle, _)| handle)
+            .ok_or(InputError::UnknownAction)?;
+
+        self.runtime
+            .trigger_haptic_vibration_action(handle, duration, frequency, amplitude)
+    }
+
+    fn update_haptics(&mut self) {
+        let now = Instant::now();
+        self.legacy_pulses.retain(|pulse| pulse.end > now);
+        for pulse in &self.legacy_pulses {
+            self.runtime
+                .trigger_haptic_pulse(pulse.tracker, 0, pulse.pulse_micros);
+        }
+
+        let requests = ::std::mem::replace(&mut self.shared.lock().haptic_requests, Vec::new());
+        for request in requests {
+            match request.target {
+                HapticTarget::Tracker(index) => {
+                    self.trigger_haptic_pulse(index, request.duration, request.amplitude)
+                }
+                HapticTarget::Action(ref action) => {
+                    if let Err(e) = self.trigger_haptic_vibration(
+                        action,
+                        request.duration,
+                        request.frequency,
+                        request.amplitude,
+                    ) {
+                        warn!("Failed to trigger haptic vibration {}: {}", action, e);
                     }
-                } else {
-                    let vertices = convert_vertices(model.vertices());
-                    let indices = model.indices().to_vec();
-
-                    Ok(Some(TrackerComponentModelInfo {
-                        component_name: model_name.to_str().ok().map(String::from),
-                        vertices,
-                        indices,
-                        texture: None,
-                    }))
                 }
-            } else {
-                let vertices = convert_vertices(model.vertices());
-                let indices = model.indices().to_vec();
-
-                Ok(Some(TrackerComponentModelInfo {
-                    component_name: model_name.to_str().ok().map(String::from),
-                    vertices,
-                    indices,
-                    texture: None,
-                }))
             }
-        } else {
-            Ok(None)
         }
     }
 
-    fn get_model_full(&self, name: &CStr) -> TrackerModelLoadStatus {
-        if let Ok(maybe_model_info) = self.load_model(&name) {
-            if let Some(mut model_info) = maybe_model_info {
-                // A complete render model isn't a component
-                model_info.component_name = None;
-                TrackerModelLoadStatus::Available(vec![model_info])
-            } else {
-                TrackerModelLoadStatus::Pending
+    fn update_actions(&mut self) {
+        if self.actions.actions.is_empty() {
+            return;
+        }
+
+        if let Some(sets) = self.shared.lock().requested_action_sets.take() {
+            self.actions.activate(&sets);
+        }
+
+        if let Err(e) = self.runtime.update_action_state(&self.actions.active_sets) {
+            warn!("Failed to update SteamVR Input action state: {}", e);
+            return;
+        }
+
+        // Pose actions are predicted like the render poses, for when the frame is displayed
+        let predicted_seconds = self.seconds_to_photons();
+
+        let mut shared = self.shared.lock();
+        for &(ref name, handle, kind) in &self.actions.actions {
+            let state = match kind {
+                ActionKind::Digital => self
+                    .runtime
+                    .digital_action_data(handle)
+                    .map(ActionState::Digital),
+                ActionKind::Analog => self
+                    .runtime
+                    .analog_action_data(handle)
+                    .map(ActionState::Analog),
+                ActionKind::Pose => self
+                    .runtime
+                    .pose_action_data(handle, self.tracking_origin, predicted_seconds)
+                    .map(ActionState::Pose),
+                ActionKind::Skeletal => self
+                    .runtime
+                    .skeletal_action_data(handle)
+                    .map(ActionState::Skeletal),
+            };
+
+            match state {
+                Ok(state) => {
+                    shared.action_states.insert(name.clone(), state);
+                }
+                Err(e) => warn!("Failed to read SteamVR Input action {}: {}", name, e),
             }
-        } else {
-            TrackerModelLoadStatus::Unavailable
         }
     }
 
-    fn get_model_components(&self, name: &CStr) -> TrackerModelLoadStatus {
-        let component_count = self.render_models.component_count(&name);
+    /// The runtime the backend is driving.
+    pub fn runtime(&self) -> &R {
+        &self.runtime
+    }
 
-        if component_count == 0 {
-            return TrackerModelLoadStatus::Unavailable;
+    /// Returns the hidden area mesh of an eye in normalized device coordinates. `Standard` and
+    /// `Inverse` meshes are triangle lists while `LineLoop` is a closed outline. The result is
+    /// empty if the runtime doesn't provide a mesh for the connected HMD, or while disconnected.
+    pub fn hidden_area_mesh(&self, eye: Eye, mesh_type: HiddenAreaMeshType) -> Vec<[f32; 2]> {
+        if !self.is_connected() {
+            return Vec::new();
         }
 
-        let load_result: StdResult<
-            Vec<Option<TrackerComponentModelInfo>>,
-            RenderModelError,
-        > = (0..component_count)
-            .map(|n| self.render_models.component_name(&name, n).unwrap())
-            .map(|component_name| self.load_model(&component_name))
-            .collect();
-
-        if let Ok(models) = load_result {
-            let mut infos = Vec::with_capacity(component_count as usize);
-            for maybe_model in models {
-                if let Some(model) = maybe_model {
-                    infos.push(model);
-                } else {
-                    return TrackerModelLoadStatus::Pending;
-                }
+        self.runtime
+            .hidden_area_mesh(eye, mesh_type)
+            .into_iter()
+            .map(|[u, v]| [u * 2.0 - 1.0, 1.0 - v * 2.0])
+            .collect()
+    }
+
+    /// Queries the descriptive properties of a tracker from the runtime. The properties of all
+    /// registered trackers are also available to systems through the `OpenVRTrackers` resource.
+    /// `None` while disconnected.
+    pub fn get_tracker_properties(&self, index: u32) -> Option<TrackerProperties> {
+        if !self.is_connected() {
+            return None;
+        }
+
+        Some(TrackerProperties::query(&self.runtime, index))
+    }
+
+    fn update_tracker_properties(&self, index: u32) {
+        let properties = match self.get_tracker_properties(index) {
+            Some(properties) => properties,
+            None => return,
+        };
+
+        let mut shared = self.shared.lock();
+        shared.trackers.insert(index, properties);
+        shared.trackers_changed = true;
+    }
+
+    /// The role the runtime currently assigns to a controller, `ControllerRole::Invalid` while
+    /// disconnected.
+    pub fn get_controller_role(&self, index: u32) -> ControllerRole {
+        if !self.is_connected() {
+            return ControllerRole::Invalid;
+        }
+
+        self.runtime.controller_role(index)
+    }
+
+    fn update_controller_roles(&self) {
+        let mut shared = self.shared.lock();
+
+        let indices: Vec<u32> = shared.trackers.keys().cloned().collect();
+        for index in indices {
+            let role = self.runtime.controller_role(index);
+
+            let changed = {
+                let properties = shared.trackers.get_mut(&index).unwrap();
+                let changed = properties.controller_role != role;
+                properties.controller_role = role;
+                changed
+            };
+
+            if changed {
+                shared.trackers_changed = true;
+                shared.push_event(OpenVREvent::ControllerRoleChanged {
+                    tracker: index,
+                    role,
+                });
             }
-            TrackerModelLoadStatus::Available(infos)
-        } else {
-            TrackerModelLoadStatus::Unavailable
         }
     }
 
+    fn is_tracker_registered(&self, index: u32) -> bool {
+        self.registered_trackers
+            .as_ref()
+            .and_then(|trackers| trackers.get(index as usize).cloned())
+            .unwrap_or(false)
+    }
+
     fn get_tracker_capabilities(&self, index: u32) -> TrackerCapabilities {
-        let render_model_components = if let Ok(name) = self.system.string_tracked_device_property(
+        let render_model_components = if let Some(name) = self.runtime.string_tracked_device_property(
             index,
             openvr_sys::ETrackedDeviceProperty_Prop_RenderModelName_String,
         ) {
-            std::cmp::max(self.render_models.component_count(&name), 1)
+            std::cmp::max(self.runtime.component_count(&name), 1)
         } else {
             0
         };
-        let is_camera = self.system.tracked_device_class(index) == TrackedDeviceClass::HMD;
+        let is_camera = self.runtime.tracked_device_class(index) == TrackedDeviceClass::HMD;
 
         TrackerCapabilities {
             render_model_components,
@@ -172,30 +777,118 @@ impl OpenVR {
     }
 }
 
-impl XRBackend for OpenVR {
+impl<R: Runtime> XRBackend for OpenVR<R> {
     fn wait(&mut self) {
-        use TrackingUniverseOrigin::Standing;
-        while let Some((event_info, _)) = self.system.poll_next_event_with_pose(Standing) {
-            println!("{:?}", event_info.event);
-            match event_info.event {
-                _ => (),
+        if !self.runtime.is_initialized() {
+            if !self.quitting {
+                self.try_reconnect();
+            }
+            if !self.runtime.is_initialized() {
+                // Requests can't be dispatched, and shouldn't pile up until reconnecting
+                self.shared.lock().haptic_requests.clear();
+                thread::sleep(Duration::from_millis(DISCONNECTED_FRAME_MILLIS));
+                return;
             }
         }
 
-        if let Ok(poses) = self.compositor.wait_get_poses() {
+        let (requested_origin, reset_requested) = {
+            let mut shared = self.shared.lock();
+            let reset_requested = shared.reset_seated_zero_pose_requested;
+            shared.reset_seated_zero_pose_requested = false;
+            (shared.requested_tracking_origin.take(), reset_requested)
+        };
+        if let Some(origin) = requested_origin {
+            self.set_tracking_origin(origin);
+        }
+        if reset_requested {
+            self.reset_seated_zero_pose();
+        }
+
+        while let Some(event_info) = self.runtime.poll_next_event(self.tracking_origin) {
+            if let Some(event) = OpenVREvent::from_event_info(&event_info) {
+                match event {
+                    OpenVREvent::QuitRequested if !self.quitting => {
+                        self.runtime.acknowledge_quit_exiting();
+                        self.quitting = true;
+                    }
+                    OpenVREvent::TrackerUpdated(index) if self.is_tracker_registered(index) => {
+                        self.update_tracker_properties(index);
+                        // Its render model might have changed
+                        self.tracker_components.remove(&index);
+                    }
+                    OpenVREvent::SeatedZeroPoseReset => self.update_seated_zero_pose(),
+                    _ => (),
+                }
+
+                self.shared.lock().push_event(event.clone());
+
+                if event == OpenVREvent::TrackerRoleChanged {
+                    self.update_controller_roles();
+                }
+            }
+        }
+
+        if self.quitting {
+            // The runtime is going away, reconnecting right away would only keep it alive. The
+            // application has to call `restart` if it keeps running, e.g. while SteamVR restarts.
+            info!("OpenVR runtime requested to quit, disconnecting");
+            self.disconnect();
+            self.shared.lock().push_event(OpenVREvent::RuntimeDisconnected);
+            return;
+        }
+
+        if let Ok(poses) = self.runtime.wait_get_poses() {
+            self.failing_since = None;
+            {
+                let tracker_poses = match self.tracker_pose_kind {
+                    PoseKind::Render => &poses.render,
+                    PoseKind::Game => &poses.game,
+                };
+
+                let mut shared = self.shared.lock();
+                shared.game_poses.clone_from(&poses.game);
+                shared.tracking_results = tracker_poses
+                    .iter()
+                    .map(|pose| pose.tracking_result)
+                    .collect();
+            }
             self.tracked_device_poses = Some(poses.render);
+            self.tracked_device_game_poses = Some(poses.game);
+            self.update_prediction();
         } else {
-            warn!("OpenVR compositor failed to wait");
+            let now = Instant::now();
+            let failing_since = match self.failing_since {
+                Some(failing_since) => failing_since,
+                None => {
+                    // Only the first failure is logged, they repeat every frame until recovering
+                    warn!("OpenVR compositor failed to wait");
+                    self.failing_since = Some(now);
+                    now
+                }
+            };
+            if now - failing_since >= Duration::from_secs(RUNTIME_LOST_AFTER_SECS) {
+                error!("Lost the OpenVR runtime, trying to reconnect");
+                self.lose_runtime();
+                return;
+            }
+
+            // The compositor didn't pace the frame, so the game loop would spin
+            thread::sleep(Duration::from_millis(DISCONNECTED_FRAME_MILLIS));
         }
+
+        self.update_controller_states();
+        self.update_component_states();
+        self.update_actions();
+        self.update_haptics();
     }
 
     fn get_new_trackers(&mut self) -> Option<Vec<(u32, TrackerCapabilities)>> {
         if let Some(ref mut registered_trackers) = self.registered_trackers {
             let mut tracker_data = None;
 
-            if let Some(poses) = self.tracked_device_poses {
-                for i in 0..16 {
-                    if !registered_trackers[i] && poses[i].device_is_connected() {
+            if let Some(ref poses) = self.tracked_device_poses {
+                for i in 0..poses.len().min(registered_trackers.len()) {
+                    if !registered_trackers[i] && poses[i].device_is_connected {
                         if tracker_data.is_none() {
                             tracker_data = Some(Vec::new());
                         }
@@ -210,14 +903,14 @@ impl XRBackend for OpenVR {
 
             tracker_data
         } else {
-            let mut trackers = [false; 16];
+            let mut trackers = vec![false; self.runtime.max_tracked_device_count() as usize];
             let mut tracker_data = Vec::new();
 
-            if let Some(poses) = self.tracked_device_poses {
-                for i in 0..16 {
+            if let Some(ref poses) = self.tracked_device_poses {
+                for i in 0..poses.len().min(trackers.len()) {
                     let pose = poses[i];
 
-                    let connected = pose.device_is_connected();
+                    let connected = pose.device_is_connected;
                     trackers[i] = connected;
                     if connected {
                         let index = i as u32;
@@ -231,8 +924,10 @@ impl XRBackend for OpenVR {
         }.map(|trackers| {
             trackers
                 .into_iter()
-                .map(|id| (id, self.get_tracker_capabilities(id)))
-                .collect()
+                .map(|id| {
+                    self.update_tracker_properties(id);
+                    (id, self.get_tracker_capabilities(id))
+                }).collect()
         })
     }
 
@@ -240,9 +935,9 @@ impl XRBackend for OpenVR {
         if let Some(ref mut registered_trackers) = self.registered_trackers {
             let mut removed_trackers = None;
 
-            if let Some(poses) = self.tracked_device_poses {
-                for i in 0..16 {
-                    if registered_trackers[i] && !poses[i].device_is_connected() {
+            if let Some(ref poses) = self.tracked_device_poses {
+                for i in 0..poses.len().min(registered_trackers.len()) {
+                    if registered_trackers[i] && !poses[i].device_is_connected {
                         if removed_trackers.is_none() {
                             removed_trackers = Some(Vec::new());
                         }
@@ -253,71 +948,81 @@ impl XRBackend for OpenVR {
                 }
             }
 
+            if let Some(ref removed_trackers) = removed_trackers {
+                let mut shared = self.shared.lock();
+                for index in removed_trackers {
+                    shared.trackers.remove(index);
+                    shared.render_model_progress.remove(index);
+                    shared.render_model_materials.remove(index);
+                    shared.component_states.remove(index);
+                    self.tracker_components.remove(index);
+                }
+                shared.trackers_changed = true;
+            }
+
             return removed_trackers;
         }
         None
     }
 
     fn get_tracker_position(&mut self, index: u32) -> TrackerPositionData {
-        if let Some(poses) = self.tracked_device_poses {
-            let pose = poses[index as usize];
-
-            let (p, q) = {
-                let mut m = pose.device_to_absolute_tracking();
-
-                let p = [m[0][3], m[1][3], m[2][3]];
+        let kind = self.tracker_pose_kind;
+        self.get_tracker_position_of_kind(index, kind)
+    }
 
-                let mut q = [
-                    (f32::max(0.0, 1.0 + m[0][0] + m[1][1] + m[2][2])).sqrt() / 2.0,
-                    (f32::max(0.0, 1.0 + m[0][0] - m[1][1] - m[2][2])).sqrt() / 2.0,
-                    (f32::max(0.0, 1.0 - m[0][0] + m[1][1] - m[2][2])).sqrt() / 2.0,
-                    (f32::max(0.0, 1.0 - m[0][0] - m[1][1] + m[2][2])).sqrt() / 2.0,
-                ];
-                q[1] = copysign(q[1], m[2][1] - m[1][2]);
-                q[2] = copysign(q[2], m[0][2] - m[2][0]);
-                q[3] = copysign(q[3], m[1][0] - m[0][1]);
+    /// The collision bounds drawn by the chaperone, or the play area rectangle if there are none,
+    /// relative to the tracking origin. Empty while the chaperone isn't calibrated, and with the
+    /// `RawAndUncalibrated` origin, which has no known relation to the play area.
+    fn get_area(&mut self) -> Vec<[f32; 3]> {
+        if !self.runtime.is_initialized() || !self.runtime.is_chaperone_calibrated() {
+            return Vec::new();
+        }
 
-                (p, q)
-            };
-            let v = pose.velocity();
-            let av = pose.angular_velocity();
-
-            let position = Vector3::new(p[0], p[1], p[2]);
-            let rotation = Quaternion::new(q[0], q[1], q[2], q[3]);
-            let velocity = Vector3::new(v[0], v[1], v[2]);
-            let angular_velocity = Vector3::new(av[0], av[1], av[2]);
-
-            TrackerPositionData {
-                position,
-                rotation,
-                velocity,
-                angular_velocity,
-                valid: pose.device_is_connected() && pose.pose_is_valid(),
-            }
+        let area = if let Some(bounds) = self.runtime.collision_bounds() {
+            bounds
+        } else if let Some(rect) = self.runtime.play_area_rect() {
+            rect.to_vec()
         } else {
-            let vec_zero = Vector3::new(0.0, 0.0, 0.0);
-            let rot_zero = Quaternion::new(0.0, 0.0, 0.0, 0.0);
-
-            TrackerPositionData {
-                position: vec_zero,
-                rotation: rot_zero,
-                velocity: vec_zero,
-                angular_velocity: vec_zero,
-                valid: false,
+            Vec::new()
+        };
+
+        // Chaperone data is always reported in standing space, while `get_tracker_position`
+        // follows the tracking origin
+        match self.tracking_origin {
+            TrackingUniverseOrigin::Standing => area,
+            TrackingUniverseOrigin::Seated => {
+                let seated_to_standing = self.runtime.seated_zero_pose_to_standing();
+                area.into_iter()
+                    .map(|p| inverse_transform_point(&seated_to_standing, p))
+                    .collect()
             }
+            TrackingUniverseOrigin::RawAndUncalibrated => Vec::new(),
         }
     }
 
-    fn get_area(&mut self) -> Vec<[f32; 3]> {
-        unimplemented!()
-    }
-
+    /// The standard hidden area mesh for both eyes. The z coordinate of each vertex is the index
+    /// of the target it belongs to, matching the order of `get_gl_target_info`.
     fn get_hidden_area_mesh(&mut self) -> Vec<[f32; 3]> {
-        unimplemented!()
+        if !self.runtime.is_initialized() {
+            return Vec::new();
+        }
+
+        [Eye::Left, Eye::Right]
+            .iter()
+            .enumerate()
+            .flat_map(|(target_index, &eye)| {
+                self.hidden_area_mesh(eye, HiddenAreaMeshType::Standard)
+                    .into_iter()
+                    .map(move |[x, y]| [x, y, target_index as f32])
+            }).collect()
     }
 
     fn get_tracker_models(&mut self, index: u32) -> TrackerModelLoadStatus {
-        let render_model_name = if let Ok(name) = self.system.string_tracked_device_property(
+        if !self.runtime.is_initialized() {
+            return TrackerModelLoadStatus::Pending;
+        }
+
+        let render_model_name = if let Some(name) = self.runtime.string_tracked_device_property(
             index,
             openvr_sys::ETrackedDeviceProperty_Prop_RenderModelName_String,
         ) {
@@ -326,28 +1031,58 @@ impl XRBackend for OpenVR {
             return TrackerModelLoadStatus::Unavailable;
         };
 
-        match self.get_model_components(&render_model_name) {
-            TrackerModelLoadStatus::Unavailable => self.get_model_full(&render_model_name),
-            load_status => load_status,
+        let poll = self.render_models.poll(&self.runtime, &render_model_name);
+
+        // Only republished when something changed, as this is polled every frame while loading
+        let mut shared = self.shared.lock();
+        if shared.render_model_progress.get(&index) != Some(&poll.progress) {
+            shared.render_model_progress.insert(index, poll.progress);
+            shared.trackers_changed = true;
         }
+        if !poll.materials.is_empty()
+            && shared.render_model_materials.get(&index) != Some(&poll.materials)
+        {
+            shared.render_model_materials.insert(index, poll.materials);
+            shared.trackers_changed = true;
+        }
+
+        poll.status
     }
 
+    /// While disconnected, the targets last known are returned, so rendering can carry on
+    /// unchanged until the backend reconnects.
     fn get_gl_target_info(&mut self, near: f32, far: f32) -> Vec<XRTargetInfo> {
         use amethyst::core::cgmath::SquareMatrix;
 
-        let left_trans = self.system.eye_to_head_transform(Eye::Left);
-        let right_trans = self.system.eye_to_head_transform(Eye::Right);
-        let left_trans = array_to_matrix(extend_matrix_array(left_trans))
+        if self.runtime.is_initialized() {
+            self.eye_targets = Some(EyeTargets {
+                size: self.runtime.recommended_render_target_size(),
+                eye_to_head: [
+                    self.runtime.eye_to_head_transform(Eye::Left),
+                    self.runtime.eye_to_head_transform(Eye::Right),
+                ],
+                projection: [
+                    self.runtime.projection_matrix(Eye::Left, near, far),
+                    self.runtime.projection_matrix(Eye::Right, near, far),
+                ],
+            });
+        }
+        let targets = match self.eye_targets {
+            Some(targets) => targets,
+            None => return Vec::new(),
+        };
+
+        let left_trans = array_to_matrix(extend_matrix_array(targets.eye_to_head[0]))
             .invert()
             .unwrap();
-        let right_trans = array_to_matrix(extend_matrix_array(right_trans))
+        let right_trans = array_to_matrix(extend_matrix_array(targets.eye_to_head[1]))
             .invert()
             .unwrap();
 
-        let left_proj = array_to_matrix(self.system.projection_matrix(Eye::Left, near, far));
-        let right_proj = array_to_matrix(self.system.projection_matrix(Eye::Right, near, far));
+        let left_proj = array_to_matrix(targets.projection[0]);
+        let right_proj = array_to_matrix(targets.projection[1]);
 
-        let size = self.system.recommended_render_target_size();
+        let size = targets.size;
 
         vec![
             XRTargetInfo {
@@ -364,6 +1099,10 @@ impl XRBackend for OpenVR {
     }
 
     fn submit_gl_target(&mut self, target_index: usize, gl_target: usize) {
+        if self.quitting || !self.runtime.is_initialized() {
+            return;
+        }
+
         let eye = match target_index {
             0 => Eye::Left,
             1 => Eye::Right,
@@ -376,53 +1115,202 @@ impl XRBackend for OpenVR {
             }
         };
 
-        // TODO: Check unsafe
-        match unsafe {
-            self.compositor.submit(
-                eye,
-                &Texture {
-                    handle: Handle::OpenGLTexture(gl_target),
-                    color_space: ColorSpace::Linear,
-                },
-                None,
-                None,
-            )
-        } {
+        match self.runtime.submit(eye, gl_target) {
             Err(e) => error!("Error submitting frame to OpenVR: {:?}", e),
             _ => (),
         }
     }
 }
 
-#[inline]
-fn copysign(a: f32, b: f32) -> f32 {
-    if b == 0.0 {
-        0.0
+/// The render model name of a tracker and the names of its components.
+fn query_components<R: Runtime>(runtime: &R, index: u32) -> TrackerComponents {
+    let model_name = match runtime.string_tracked_device_property(
+        index,
+        openvr_sys::ETrackedDeviceProperty_Prop_RenderModelName_String,
+    ) {
+        Some(name) => name,
+        None => {
+            return TrackerComponents {
+                model_name: CString::default(),
+                components: Vec::new(),
+            }
+        }
+    };
+
+    let components = (0..runtime.component_count(&model_name))
+        .filter_map(|n| runtime.component_name(&model_name, n))
+        .map(|name| (name, None))
+        .collect();
+    TrackerComponents {
+        model_name,
+        components,
+    }
+}
+
+pub(crate) fn position_data(pose: Option<&DevicePose>) -> TrackerPositionData {
+    if let Some(pose) = pose {
+        let m = pose.device_to_absolute_tracking;
+        let v = pose.velocity;
+        let av = pose.angular_velocity;
+
+        let position = Vector3::new(m[0][3], m[1][3], m[2][3]);
+        let rotation = matrix_to_quaternion(&m);
+        let velocity = Vector3::new(v[0], v[1], v[2]);
+        let angular_velocity = Vector3::new(av[0], av[1], av[2]);
+
+        TrackerPositionData {
+            position,
+            rotation,
+            velocity,
+            angular_velocity,
+            valid: pose.device_is_connected && pose.pose_is_valid,
+        }
     } else {
-        a.abs() * b.signum()
+        let vec_zero = Vector3::new(0.0, 0.0, 0.0);
+        let rot_zero = Quaternion::new(0.0, 0.0, 0.0, 0.0);
+
+        TrackerPositionData {
+            position: vec_zero,
+            rotation: rot_zero,
+            velocity: vec_zero,
+            angular_velocity: vec_zero,
+            valid: false,
+        }
     }
 }
 
-#[inline]
-fn convert_vertices(vertices: &[openvr::render_models::Vertex]) -> Vec<TrackerComponentVertex> {
+/// Extracts the rotation of a row-major 3x4 transform using Shepperd's method, which divides by
+/// the largest of the quaternion's components to stay accurate for any rotation.
+fn matrix_to_quaternion(m: &[[f32; 4]; 3]) -> Quaternion<f32> {
+    let trace = m[0][0] + m[1][1] + m[2][2];
+
+    let (w, x, y, z) = if trace > 0.0 {
+        let s = (1.0 + trace).sqrt() * 2.0;
+        (
+            0.25 * s,
+            (m[2][1] - m[1][2]) / s,
+            (m[0][2] - m[2][0]) / s,
+            (m[1][0] - m[0][1]) / s,
+        )
+    } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+        let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+        (
+            (m[2][1] - m[1][2]) / s,
+            0.25 * s,
+            (m[0][1] + m[1][0]) / s,
+            (m[0][2] + m[2][0]) / s,
+        )
+    } else if m[1][1] > m[2][2] {
+        let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+        (
+            (m[0][2] - m[2][0]) / s,
+            (m[0][1] + m[1][0]) / s,
+            0.25 * s,
+            (m[1][2] + m[2][1]) / s,
+        )
+    } else {
+        let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+        (
+            (m[1][0] - m[0][1]) / s,
+            (m[0][2] + m[2][0]) / s,
+            (m[1][2] + m[2][1]) / s,
+            0.25 * s,
+        )
+    };
+
+    // Tracking matrices aren't perfectly orthonormal, so the result needs normalizing
+    Quaternion::new(w, x, y, z).normalize()
+}
+
+/// Converts render model vertices for amethyst, generating tangents from the positions and
+/// texture coordinates of the triangles around each vertex, weighted by the angle at the vertex.
+pub(crate) fn convert_vertices(
+    vertices: &[openvr::render_models::Vertex],
+    indices: &[u16],
+) -> Vec<TrackerComponentVertex> {
+    // amethyst's texture coordinates are flipped vertically, and the tangents have to follow them
+    let tex_coords: Vec<[f32; 2]> = vertices
+        .iter()
+        .map(|vert| [vert.texture_coord[0], 1.0 - vert.texture_coord[1]])
+        .collect();
+    let mut tangents = vec![Vector3::new(0.0, 0.0, 0.0); vertices.len()];
+
+    for triangle in indices.chunks(3).filter(|triangle| triangle.len() == 3) {
+        let corners = [
+            triangle[0] as usize,
+            triangle[1] as usize,
+            triangle[2] as usize,
+        ];
+        if corners.iter().any(|&i| i >= vertices.len()) {
+            continue;
+        }
+
+        let p = [
+            Vector3::from(vertices[corners[0]].position),
+            Vector3::from(vertices[corners[1]].position),
+            Vector3::from(vertices[corners[2]].position),
+        ];
+        let e1 = p[1] - p[0];
+        let e2 = p[2] - p[0];
+        let [u0, v0] = tex_coords[corners[0]];
+        let [u1, v1] = tex_coords[corners[1]];
+        let [u2, v2] = tex_coords[corners[2]];
+        let (du1, dv1) = (u1 - u0, v1 - v0);
+        let (du2, dv2) = (u2 - u0, v2 - v0);
+
+        // Triangles without area in either space don't define a tangent, which shows as a zero
+        // cross product or a division by zero
+        let det = du1 * dv2 - du2 * dv1;
+        let tangent = (e1 * dv2 - e2 * dv1) / det;
+        if !e1.cross(e2).magnitude2().is_normal() || !tangent.magnitude2().is_normal() {
+            continue;
+        }
+        let tangent = tangent.normalize();
+
+        for corner in 0..3 {
+            let to_next = p[(corner + 1) % 3] - p[corner];
+            let to_previous = p[(corner + 2) % 3] - p[corner];
+            tangents[corners[corner]] += tangent * to_next.angle(to_previous).0;
+        }
+    }
+
     vertices
         .iter()
-        .map(|vert| {
-            let normal_vector = Vector3::from(vert.normal);
-            let up = Vector3::from([0.0, 1.0, 0.0]);
-            let tangent = normal_vector.cross(up).cross(normal_vector).into();
-            let [u, v] = vert.texture_coord;
-            TrackerComponentVertex {
-                position: vert.position,
-                normal: vert.normal,
-                tangent,
-                tex_coord: [u, 1.0 - v],
-            }
+        .zip(tex_coords)
+        .zip(tangents)
+        .map(|((vert, tex_coord), tangent)| TrackerComponentVertex {
+            position: vert.position,
+            normal: vert.normal,
+            tangent: orthogonal_tangent(Vector3::from(vert.normal), tangent).into(),
+            tex_coord,
         }).collect()
 }
 
+/// Makes an accumulated tangent perpendicular to the normal. Vertices which are only part of
+/// degenerate triangles have no tangent, so they get an arbitrary perpendicular one.
+fn orthogonal_tangent(normal: Vector3<f32>, tangent: Vector3<f32>) -> Vector3<f32> {
+    let normal = if normal.magnitude2() > ::std::f32::EPSILON {
+        normal.normalize()
+    } else {
+        Vector3::unit_z()
+    };
+
+    let tangent = tangent - normal * normal.dot(tangent);
+    if tangent.magnitude2() > ::std::f32::EPSILON {
+        return tangent.normalize();
+    }
+
+    // Cross with the axis least parallel to the normal, so the result can't degenerate
+    let axis = if normal.x.abs() < 0.9 {
+        Vector3::unit_x()
+    } else {
+        Vector3::unit_y()
+    };
+    normal.cross(axis).normalize()
+}
+
 #[inline]
-fn array_to_matrix(arr: [[f32; 4]; 4]) -> Matrix4<f32> {
+pub(crate) fn array_to_matrix(arr: [[f32; 4]; 4]) -> Matrix4<f32> {
     Matrix4::new(
         arr[0][0], arr[1][0], arr[2][0], arr[3][0], arr[0][1], arr[1][1], arr[2][1], arr[3][1],
         arr[0][2], arr[1][2], arr[2][2], arr[3][2], arr[0][3], arr[1][3], arr[2][3], arr[3][3],
@@ -430,7 +1318,7 @@ fn array_to_matrix(arr: [[f32; 4]; 4]) -> Matrix4<f32> {
 }
 
 #[inline]
-fn extend_matrix_array(arr: [[f32; 4]; 3]) -> [[f32; 4]; 4] {
+pub(crate) fn extend_matrix_array(arr: [[f32; 4]; 3]) -> [[f32; 4]; 4] {
     [
         [arr[0][0], arr[0][1], arr[0][2], arr[0][3]],
         [arr[1][0], arr[1][1], arr[1][2], arr[1][3]],
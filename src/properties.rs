@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use openvr::TrackedDeviceClass;
+use openvr_sys as sys;
+
+use render_models::{ComponentMaterial, RenderModelProgress};
+use runtime::Runtime;
+
+/// The role a controller has been assigned by the runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControllerRole {
+    /// The device isn't a controller, or hasn't been assigned a role yet.
+    Invalid,
+    LeftHand,
+    RightHand,
+    /// The controller doesn't want to be treated as a hand.
+    OptOut,
+    Treadmill,
+}
+
+impl ControllerRole {
+    pub(crate) fn from_sys(role: sys::ETrackedControllerRole) -> ControllerRole {
+        match role {
+            sys::ETrackedControllerRole_TrackedControllerRole_LeftHand => ControllerRole::LeftHand,
+            sys::ETrackedControllerRole_TrackedControllerRole_RightHand => {
+                ControllerRole::RightHand
+            }
+            sys::ETrackedControllerRole_TrackedControllerRole_OptOut => ControllerRole::OptOut,
+            sys::ETrackedControllerRole_TrackedControllerRole_Treadmill => {
+                ControllerRole::Treadmill
+            }
+            _ => ControllerRole::Invalid,
+        }
+    }
+}
+
+/// Descriptive properties of a tracked device, for labeling and assigning devices.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackerProperties {
+    pub class: TrackedDeviceClass,
+    pub serial_number: Option<String>,
+    pub manufacturer: Option<String>,
+    pub model_number: Option<String>,
+    pub render_model_name: Option<String>,
+    pub controller_role: ControllerRole,
+    /// Role assigned to a Vive tracker in the SteamVR settings, e.g. `"waist"` or `"left_foot"`.
+    pub tracker_role: Option<String>,
+    /// Battery charge between 0 and 1, if the device reports it.
+    pub battery_percentage: Option<f32>,
+    pub is_charging: Option<bool>,
+}
+
+impl TrackerProperties {
+    pub(crate) fn query<R: Runtime>(runtime: &R, index: u32) -> TrackerProperties {
+        let string = |property| {
+            runtime
+                .string_tracked_device_property(index, property)
+                .and_then(|value| value.into_string().ok())
+        };
+
+        let tracker_role = string(sys::ETrackedDeviceProperty_Prop_ControllerType_String)
+            .and_then(|ty| {
+                if ty.starts_with("vive_tracker_") {
+                    Some(ty["vive_tracker_".len()..].to_owned())
+                } else {
+                    None
+                }
+            });
+
+        let provides_battery = runtime
+            .bool_tracked_device_property(
+                index,
+                sys::ETrackedDeviceProperty_Prop_DeviceProvidesBatteryStatus_Bool,
+            ).unwrap_or(false);
+        let (battery_percentage, is_charging) = if provides_battery {
+            (
+                runtime.float_tracked_device_property(
+                    index,
+                    sys::ETrackedDeviceProperty_Prop_DeviceBatteryPercentage_Float,
+                ),
+                runtime.bool_tracked_device_property(
+                    index,
+                    sys::ETrackedDeviceProperty_Prop_DeviceIsCharging_Bool,
+                ),
+            )
+        } else {
+            (None, None)
+        };
+
+        TrackerProperties {
+            class: runtime.tracked_device_class(index),
+            serial_number: string(sys::ETrackedDeviceProperty_Prop_SerialNumber_String),
+            manufacturer: string(sys::ETrackedDeviceProperty_Prop_ManufacturerName_String),
+            model_number: string(sys::ETrackedDeviceProperty_Prop_ModelNumber_String),
+            render_model_name: string(sys::ETrackedDeviceProperty_Prop_RenderModelName_String),
+            controller_role: runtime.controller_role(index),
+            tracker_role,
+            battery_percentage,
+            is_charging,
+        }
+    }
+}
+
+/// Resource holding the properties of every tracker currently known to the backend, keyed by
+/// tracker index. Kept up to date by the `OpenVRSystem`.
+#[derive(Default)]
+pub struct OpenVRTrackers {
+    pub(crate) properties: HashMap<u32, TrackerProperties>,
+    pub(crate) render_model_progress: HashMap<u32, RenderModelProgress>,
+    pub(crate) render_model_materials: HashMap<u32, Vec<ComponentMaterial>>,
+}
+
+impl OpenVRTrackers {
+    pub fn get(&self, index: u32) -> Option<&TrackerProperties> {
+        self.properties.get(&index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &TrackerProperties)> {
+        self.properties.iter().map(|(&index, properties)| (index, properties))
+    }
+
+    /// How far loading the render model of a tracker has come, once amethyst started loading it.
+    pub fn render_model_progress(&self, index: u32) -> Option<RenderModelProgress> {
+        self.render_model_progress.get(&index).cloned()
+    }
+
+    /// The material of each component of a tracker's render model, in the order of the
+    /// components amethyst received. Empty until the model is available.
+    pub fn render_model_materials(&self, index: u32) -> &[ComponentMaterial] {
+        self.render_model_materials
+            .get(&index)
+            .map_or(&[], |materials| materials.as_slice())
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::sync::Arc;
+
+use amethyst::xr::{
+    TrackerComponentModelInfo, TrackerComponentTextureData, TrackerComponentVertex,
+    TrackerModelLoadStatus,
+};
+
+use convert_vertices;
+use runtime::{
+    RenderModel, RenderModelError, RenderModelResult, RenderModelTexture, Runtime, TextureId,
+};
+
+/// How far loading the render model of a tracker has come. Available to systems through
+/// `OpenVRTrackers::render_model_progress`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderModelProgress {
+    /// Components which finished loading. A model without components counts as one.
+    pub loaded: u32,
+    pub total: u32,
+}
+
+impl RenderModelProgress {
+    pub fn is_complete(&self) -> bool {
+        self.loaded == self.total
+    }
+}
+
+/// Material information of a loaded render model component, for what
+/// `TrackerComponentModelInfo` can't carry. OpenVR render models only have a diffuse texture.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComponentMaterial {
+    /// Matches `TrackerComponentModelInfo::component_name`.
+    pub component_name: Option<String>,
+    pub diffuse_texture_id: Option<TextureId>,
+    /// Width and height of the diffuse texture, if it loaded.
+    pub diffuse_texture_size: Option<(u16, u16)>,
+    /// Why the diffuse texture failed to load, in which case the component is untextured.
+    pub texture_error: Option<RenderModelError>,
+}
+
+/// The result of polling a render model.
+pub(crate) struct ModelPoll {
+    pub status: TrackerModelLoadStatus,
+    pub progress: RenderModelProgress,
+    /// The material of every component, once the model is available.
+    pub materials: Vec<ComponentMaterial>,
+}
+
+enum LoadState {
+    /// The component's model hasn't loaded yet.
+    LoadingModel,
+    /// The model has loaded, but its texture hasn't.
+    LoadingTexture(Arc<RenderModel>),
+    Loaded(TrackerComponentModelInfo, ComponentMaterial),
+    Failed,
+}
+
+struct Component {
+    /// The name the component's model is loaded by.
+    model_name: CString,
+    /// `None` when the whole render model is loaded as one.
+    component_name: Option<String>,
+    state: LoadState,
+}
+
+impl Component {
+    fn new(model_name: CString, component_name: Option<String>) -> Component {
+        Component {
+            model_name,
+            component_name,
+            state: LoadState::LoadingModel,
+        }
+    }
+
+    fn is_loaded(&self) -> bool {
+        match self.state {
+            LoadState::Loaded(..) => true,
+            _ => false,
+        }
+    }
+
+    fn has_failed(&self) -> bool {
+        match self.state {
+            LoadState::Failed => true,
+            _ => false,
+        }
+    }
+
+    /// Advances loading as far as the runtime allows without blocking.
+    fn poll<R: Runtime>(&mut self, runtime: &R, cache: &mut Cache) {
+        if let LoadState::LoadingModel = self.state {
+            match cache.load_model(runtime, &self.model_name) {
+                Ok(Some(model)) => self.state = LoadState::LoadingTexture(model),
+                Ok(None) => return,
+                Err(e) => {
+                    warn!(
+                        "Failed to load render model {}: {}",
+                        self.model_name.to_string_lossy(),
+                        e.0
+                    );
+                    self.state = LoadState::Failed;
+                    return;
+                }
+            }
+        }
+
+        let texture = match self.state {
+            LoadState::LoadingTexture(ref model) => match model.diffuse_texture_id {
+                Some(texture_id) => match cache.load_texture(runtime, texture_id) {
+                    Ok(Some(texture)) => Ok(Some(texture)),
+                    // Still loading, unlike an error
+                    Ok(None) => return,
+                    Err(e) => {
+                        warn!(
+                            "Failed to load texture {} of render model {}: {}",
+                            texture_id,
+                            self.model_name.to_string_lossy(),
+                            e.0
+                        );
+                        Err(e)
+                    }
+                },
+                None => Ok(None),
+            },
+            _ => return,
+        };
+
+        if let LoadState::LoadingTexture(model) = mem::replace(&mut self.state, LoadState::Failed) {
+            self.state = self.loaded(&model, texture, cache);
+        }
+    }
+
+    /// The model is still usable when its texture failed to load, just untextured.
+    fn loaded(
+        &self,
+        model: &RenderModel,
+        texture: RenderModelResult<Arc<RenderModelTexture>>,
+        cache: &mut Cache,
+    ) -> LoadState {
+        let (texture, texture_error) = match texture {
+            Ok(texture) => (texture, None),
+            Err(e) => (None, Some(e)),
+        };
+
+        let material = ComponentMaterial {
+            component_name: self.component_name.clone(),
+            diffuse_texture_id: model.diffuse_texture_id,
+            diffuse_texture_size: texture.as_ref().map(|texture| texture.size),
+            texture_error,
+        };
+        let info = TrackerComponentModelInfo {
+            component_name: self.component_name.clone(),
+            vertices: cache.converted_vertices(&self.model_name, model),
+            indices: model.indices.clone(),
+            texture: texture.map(|texture| TrackerComponentTextureData {
+                data: texture.data.clone(),
+                size: texture.size,
+            }),
+        };
+
+        LoadState::Loaded(info, material)
+    }
+}
+
+/// Render models and textures loaded from the runtime this session, so identical devices only
+/// load and convert them once.
+#[derive(Default)]
+struct Cache {
+    models: HashMap<CString, Arc<RenderModel>>,
+    textures: HashMap<TextureId, Arc<RenderModelTexture>>,
+    /// Vertices of the models, by name, converted for amethyst.
+    vertices: HashMap<CString, Vec<TrackerComponentVertex>>,
+}
+
+impl Cache {
+    fn converted_vertices(
+        &mut self,
+        name: &CStr,
+        model: &RenderModel,
+    ) -> Vec<TrackerComponentVertex> {
+        // `TrackerComponentModelInfo` owns its vertices, so every device still gets a copy
+        self.vertices
+            .entry(name.to_owned())
+            .or_insert_with(|| convert_vertices(&model.vertices, &model.indices))
+            .clone()
+    }
+
+    fn load_model<R: Runtime>(
+        &mut self,
+        runtime: &R,
+        name: &CStr,
+    ) -> RenderModelResult<Arc<RenderModel>> {
+        if let Some(model) = self.models.get(name) {
+            return Ok(Some(model.clone()));
+        }
+
+        let model = runtime.load_render_model(name)?.map(Arc::new);
+        if let Some(ref model) = model {
+            self.models.insert(name.to_owned(), model.clone());
+        }
+        Ok(model)
+    }
+
+    fn load_texture<R: Runtime>(
+        &mut self,
+        runtime: &R,
+        id: TextureId,
+    ) -> RenderModelResult<Arc<RenderModelTexture>> {
+        if let Some(texture) = self.textures.get(&id) {
+            return Ok(Some(texture.clone()));
+        }
+
+        let texture = runtime.load_texture(id)?.map(Arc::new);
+        if let Some(ref texture) = texture {
+            self.textures.insert(id, texture.clone());
+        }
+        Ok(texture)
+    }
+}
+
+/// Loads render models incrementally. Every poll only advances the components which haven't
+/// finished loading yet, so components which did are kept instead of being loaded again.
+///
+/// The loader belongs to one runtime session, as texture ids aren't valid across sessions.
+#[derive(Default)]
+pub(crate) struct RenderModelLoader {
+    loads: HashMap<CString, Vec<Component>>,
+    cache: Cache,
+}
+
+impl RenderModelLoader {
+    /// Advances loading the named render model. Models with components are loaded component by
+    /// component, falling back to the model as a whole if any of them fails to load.
+    pub fn poll<R: Runtime>(&mut self, runtime: &R, name: &CStr) -> ModelPoll {
+        let cache = &mut self.cache;
+        let components = self
+            .loads
+            .entry(name.to_owned())
+            .or_insert_with(|| start_load(runtime, name));
+
+        for component in components.iter_mut() {
+            component.poll(runtime, cache);
+        }
+
+        if components.iter().any(Component::has_failed) {
+            let is_whole_model = components.len() == 1 && components[0].component_name.is_none();
+            if !is_whole_model {
+                *components = vec![Component::new(name.to_owned(), None)];
+                components[0].poll(runtime, cache);
+            }
+        }
+
+        let progress = RenderModelProgress {
+            loaded: components.iter().filter(|c| c.is_loaded()).count() as u32,
+            total: components.len() as u32,
+        };
+
+        let status = if components.iter().any(Component::has_failed) {
+            // Kept around so the model isn't loaded again on every poll
+            Some(TrackerModelLoadStatus::Unavailable)
+        } else if !progress.is_complete() {
+            Some(TrackerModelLoadStatus::Pending)
+        } else {
+            None
+        };
+        if let Some(status) = status {
+            return ModelPoll {
+                status,
+                progress,
+                materials: Vec::new(),
+            };
+        }
+
+        let (infos, materials) = self
+            .loads
+            .remove(name)
+            .unwrap()
+            .into_iter()
+            .filter_map(|component| match component.state {
+                LoadState::Loaded(info, material) => Some((info, material)),
+                _ => None,
+            }).unzip();
+
+        ModelPoll {
+            status: TrackerModelLoadStatus::Available(infos),
+            progress,
+            materials,
+        }
+    }
+}
+
+fn start_load<R: Runtime>(runtime: &R, name: &CStr) -> Vec<Component> {
+    let components: Vec<Component> = (0..runtime.component_count(name))
+        .filter_map(|n| runtime.component_name(name, n))
+        .map(|component_name| {
+            let display_name = component_name.to_str().ok().map(String::from);
+            Component::new(component_name, display_name)
+        }).collect();
+
+    if components.is_empty() {
+        vec![Component::new(name.to_owned(), None)]
+    } else {
+        components
+    }
+}
@@ -0,0 +1,73 @@
+use std::ptr;
+
+use openvr_sys as sys;
+
+use super::interface::load_interface;
+
+/// Thin wrapper around the chaperone interfaces, which the `openvr` crate doesn't expose fully.
+pub(crate) struct Chaperone {
+    chaperone: &'static sys::VR_IVRChaperone_FnTable,
+    chaperone_setup: Option<&'static sys::VR_IVRChaperoneSetup_FnTable>,
+}
+
+impl Chaperone {
+    pub fn new() -> Option<Chaperone> {
+        // Both interfaces are only valid while the OpenVR context is alive
+        let chaperone = unsafe {
+            load_interface::<sys::VR_IVRChaperone_FnTable>(sys::IVRChaperone_Version)?
+        };
+        let chaperone_setup = unsafe {
+            load_interface::<sys::VR_IVRChaperoneSetup_FnTable>(sys::IVRChaperoneSetup_Version)
+        };
+
+        Some(Chaperone {
+            chaperone,
+            chaperone_setup,
+        })
+    }
+
+    /// Returns `false` if the user hasn't set up their play area or the tracking system has lost
+    /// its calibration. Warnings (e.g. a base station having moved) are still considered usable.
+    pub fn is_calibrated(&self) -> bool {
+        let state = unsafe { self.chaperone.GetCalibrationState.unwrap()() };
+        state < sys::ChaperoneCalibrationState_ChaperoneCalibrationState_Error
+    }
+
+    /// The four corners of the play area rectangle in standing space.
+    pub fn play_area_rect(&self) -> Option<[[f32; 3]; 4]> {
+        let mut rect: sys::HmdQuad_t = unsafe { ::std::mem::zeroed() };
+        if unsafe { self.chaperone.GetPlayAreaRect.unwrap()(&mut rect) } {
+            Some([
+                rect.vCorners[0].v,
+                rect.vCorners[1].v,
+                rect.vCorners[2].v,
+                rect.vCorners[3].v,
+            ])
+        } else {
+            None
+        }
+    }
+
+    /// The floor polygon of the user's collision bounds in standing space, if the runtime
+    /// provides one.
+    pub fn collision_bounds(&self) -> Option<Vec<[f32; 3]>> {
+        let setup = self.chaperone_setup?;
+        let get_bounds = setup.GetLiveCollisionBoundsInfo?;
+
+        let mut count = 0u32;
+        unsafe { get_bounds(ptr::null_mut(), &mut count) };
+        if count == 0 {
+            return None;
+        }
+
+        let mut quads: Vec<sys::HmdQuad_t> = vec![unsafe { ::std::mem::zeroed() }; count as usize];
+        if !unsafe { get_bounds(quads.as_mut_ptr(), &mut count) } {
+            return None;
+        }
+        quads.truncate(count as usize);
+
+        // Each quad is a wall segment, the first corner of each sits on the floor and the next
+        // quad continues from where the last one ended.
+        Some(quads.iter().map(|quad| quad.vCorners[0].v).collect())
+    }
+}
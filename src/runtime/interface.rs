@@ -0,0 +1,18 @@
+use openvr_sys as sys;
+
+/// Loads the function table of an OpenVR interface that the `openvr` crate doesn't wrap.
+///
+/// Unsafe since the returned table is only valid while the OpenVR context is alive.
+pub(crate) unsafe fn load_interface<T>(version: &[u8]) -> Option<&'static T> {
+    let mut name = Vec::from(&b"FnTable:"[..]);
+    name.extend(version);
+
+    let mut error = sys::EVRInitError_VRInitError_None;
+    let table = sys::VR_GetGenericInterface(name.as_ptr() as *const _, &mut error);
+
+    if error != sys::EVRInitError_VRInitError_None || table == 0 {
+        None
+    } else {
+        Some(&*(table as *const T))
+    }
+}
@@ -0,0 +1,752 @@
+//! A scriptable in-memory runtime for driving the backend without a headset.
+//!
+//! `MockRuntime` is a cheap handle to shared state, so a clone can be kept around to script the
+//! runtime after the original has been moved into an `OpenVR` backend:
+//!
+//! ```ignore
+//! let runtime = MockRuntime::new();
+//! let mut backend = OpenVR::with_runtime(runtime.clone());
+//!
+//! runtime.connect_device(0, TrackedDeviceClass::HMD);
+//! backend.wait();
+//! assert_eq!(backend.get_new_trackers().unwrap().len(), 1);
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::ffi::{CStr, CString};
+use std::result::Result as StdResult;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use openvr::system::event::{Event, EventInfo, SeatedZeroPoseReset};
+use openvr::system::HiddenAreaMeshType;
+use openvr::{Eye, TrackedDeviceClass, TrackingUniverseOrigin};
+use openvr_sys as sys;
+
+use controller::ControllerState;
+use error::OpenVRError;
+use input::{
+    ActionHandle, ActionSetHandle, ActionState, AnalogActionData, DigitalActionData, InputError,
+    PoseActionData, SkeletalActionData,
+};
+use properties::ControllerRole;
+
+use super::{
+    ComponentState, CompositorError, DevicePose, FramePoses, RenderModel, RenderModelError,
+    RenderModelResult, RenderModelTexture, Runtime, TextureId, TrackedDeviceProperty,
+    TrackingResult,
+};
+
+#[derive(Clone, Default)]
+pub struct MockRuntime {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    devices: HashMap<u32, MockDevice>,
+    events: VecDeque<EventInfo>,
+    wait_errors: VecDeque<CompositorError>,
+    submitted: Vec<(Eye, usize)>,
+    quit_acknowledged: bool,
+    shut_down: bool,
+    reinit_errors: VecDeque<OpenVRError>,
+    time_since_last_vsync: f32,
+    tracking_space: Option<TrackingUniverseOrigin>,
+    seated_zero_pose: Option<[[f32; 4]; 3]>,
+    haptic_pulses: Vec<(u32, u32, u16)>,
+    haptic_vibrations: Vec<(ActionHandle, f32, f32, f32)>,
+
+    render_models: HashMap<CString, MockLoad<RenderModel>>,
+    components: HashMap<CString, Vec<CString>>,
+    component_states: HashMap<(u32, CString), ComponentState>,
+    textures: HashMap<TextureId, MockLoad<RenderModelTexture>>,
+
+    hidden_area_meshes: HashMap<(bool, u32), Vec<[f32; 2]>>,
+    chaperone_calibrated: bool,
+    play_area_rect: Option<[[f32; 3]; 4]>,
+    collision_bounds: Option<Vec<[f32; 3]>>,
+
+    input_available: bool,
+    action_manifest_path: Option<CString>,
+    /// Handles of action sets and actions, which share a namespace since both are paths.
+    input_handles: HashMap<CString, u64>,
+    updated_action_sets: Vec<ActionSetHandle>,
+    action_states: HashMap<ActionHandle, ActionState>,
+}
+
+struct MockDevice {
+    class: TrackedDeviceClass,
+    role: ControllerRole,
+    controller_state: Option<ControllerState>,
+    pose: DevicePose,
+    /// Returned as the game pose instead of `pose`, if set.
+    game_pose: Option<DevicePose>,
+    properties: HashMap<TrackedDeviceProperty, MockProperty>,
+}
+
+#[derive(Clone)]
+enum MockProperty {
+    String(CString),
+    Float(f32),
+    Bool(bool),
+}
+
+/// An asset that becomes available after being polled a number of times.
+struct MockLoad<T> {
+    value: StdResult<T, RenderModelError>,
+    pending_polls: u32,
+}
+
+impl<T: Clone> MockLoad<T> {
+    fn poll(&mut self) -> RenderModelResult<T> {
+        if self.pending_polls > 0 {
+            self.pending_polls -= 1;
+            Ok(None)
+        } else {
+            self.value.clone().map(Some)
+        }
+    }
+}
+
+impl MockRuntime {
+    pub fn new() -> MockRuntime {
+        MockRuntime::default()
+    }
+
+    fn state(&self) -> MutexGuard<MockState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Connects a device with a valid identity pose at the given index.
+    pub fn connect_device(&self, index: u32, class: TrackedDeviceClass) {
+        let pose = DevicePose {
+            tracking_result: TrackingResult::RunningOk,
+            pose_is_valid: true,
+            device_is_connected: true,
+            ..DevicePose::default()
+        };
+
+        self.state().devices.insert(
+            index,
+            MockDevice {
+                class,
+                role: ControllerRole::Invalid,
+                controller_state: None,
+                pose,
+                game_pose: None,
+                properties: HashMap::new(),
+            },
+        );
+    }
+
+    pub fn disconnect_device(&self, index: u32) {
+        self.state().devices.remove(&index);
+    }
+
+    /// Sets the pose returned for a connected device from the next `wait_get_poses` on.
+    pub fn set_pose(&self, index: u32, pose: DevicePose) {
+        if let Some(device) = self.state().devices.get_mut(&index) {
+            device.pose = pose;
+        }
+    }
+
+    /// Sets the game pose returned for a connected device from the next `wait_get_poses` on. It's
+    /// the same as the render pose by default.
+    pub fn set_game_pose(&self, index: u32, pose: DevicePose) {
+        if let Some(device) = self.state().devices.get_mut(&index) {
+            device.game_pose = Some(pose);
+        }
+    }
+
+    /// Changes the role of a device. Push a `TrackedDeviceRoleChanged` event to notify the
+    /// backend, like the runtime would.
+    pub fn set_controller_role(&self, index: u32, role: ControllerRole) {
+        if let Some(device) = self.state().devices.get_mut(&index) {
+            device.role = role;
+        }
+    }
+
+    /// Sets the legacy controller state of a device, making it a controller.
+    pub fn set_controller_state(&self, index: u32, controller_state: ControllerState) {
+        if let Some(device) = self.state().devices.get_mut(&index) {
+            device.controller_state = Some(controller_state);
+        }
+    }
+
+    pub fn set_time_since_last_vsync(&self, seconds: f32) {
+        self.state().time_since_last_vsync = seconds;
+    }
+
+    /// The origin last passed to `set_tracking_space`, if any.
+    pub fn tracking_space(&self) -> Option<TrackingUniverseOrigin> {
+        self.state().tracking_space
+    }
+
+    /// Sets the seated zero pose relative to the standing origin, which is the identity by
+    /// default. Resetting the seated zero pose moves it to the pose of device 0.
+    pub fn set_seated_zero_pose(&self, pose: [[f32; 4]; 3]) {
+        self.state().seated_zero_pose = Some(pose);
+    }
+
+    /// Returns and clears the legacy haptic pulses triggered so far, as
+    /// `(device, axis, duration_micros)`.
+    pub fn take_haptic_pulses(&self) -> Vec<(u32, u32, u16)> {
+        ::std::mem::replace(&mut self.state().haptic_pulses, Vec::new())
+    }
+
+    /// Returns and clears the haptic vibration actions triggered so far, as
+    /// `(action, duration, frequency, amplitude)`. Action handles can be looked up with
+    /// `input_handle`.
+    pub fn take_haptic_vibrations(&self) -> Vec<(ActionHandle, f32, f32, f32)> {
+        ::std::mem::replace(&mut self.state().haptic_vibrations, Vec::new())
+    }
+
+    /// The handle the mock hands out for an action or action set path.
+    pub fn input_handle(&self, name: &str) -> u64 {
+        self.state().input_handle(&CString::new(name).unwrap())
+    }
+
+    /// Makes SteamVR Input available or not, it isn't by default.
+    pub fn set_input_available(&self, available: bool) {
+        self.state().input_available = available;
+    }
+
+    pub fn action_manifest_path(&self) -> Option<CString> {
+        self.state().action_manifest_path.clone()
+    }
+
+    /// The action sets passed to the last `update_action_state`, by name.
+    pub fn updated_action_sets(&self) -> Vec<String> {
+        let state = self.state();
+        state
+            .input_handles
+            .iter()
+            .filter(|&(_, handle)| state.updated_action_sets.contains(handle))
+            .map(|(name, _)| name.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    /// Sets the state returned for an action from the next `update_action_state` on.
+    pub fn set_action_state(&self, name: &str, action_state: ActionState) {
+        let mut state = self.state();
+        let handle = state.input_handle(&CString::new(name).unwrap());
+        state.action_states.insert(handle, action_state);
+    }
+
+    fn set_property(&self, index: u32, property: TrackedDeviceProperty, value: MockProperty) {
+        if let Some(device) = self.state().devices.get_mut(&index) {
+            device.properties.insert(property, value);
+        }
+    }
+
+    fn property(&self, index: u32, property: TrackedDeviceProperty) -> Option<MockProperty> {
+        self.state()
+            .devices
+            .get(&index)
+            .and_then(|device| device.properties.get(&property).cloned())
+    }
+
+    pub fn set_string_property(&self, index: u32, property: TrackedDeviceProperty, value: &str) {
+        let value = MockProperty::String(CString::new(value).unwrap());
+        self.set_property(index, property, value);
+    }
+
+    pub fn set_float_property(&self, index: u32, property: TrackedDeviceProperty, value: f32) {
+        self.set_property(index, property, MockProperty::Float(value));
+    }
+
+    pub fn set_bool_property(&self, index: u32, property: TrackedDeviceProperty, value: bool) {
+        self.set_property(index, property, MockProperty::Bool(value));
+    }
+
+    /// Queues an event to be returned by `poll_next_event`.
+    pub fn push_event(&self, tracked_device_index: u32, event: Event) {
+        self.state().events.push_back(EventInfo {
+            tracked_device_index,
+            age: 0.0,
+            event,
+        });
+    }
+
+    /// Makes the next call to `wait_get_poses` fail with the given error.
+    pub fn fail_next_wait(&self, error: CompositorError) {
+        self.state().wait_errors.push_back(error);
+    }
+
+    /// Returns and clears the frames submitted so far.
+    pub fn take_submitted(&self) -> Vec<(Eye, usize)> {
+        ::std::mem::replace(&mut self.state().submitted, Vec::new())
+    }
+
+    /// Whether the backend acknowledged a quit request.
+    pub fn quit_acknowledged(&self) -> bool {
+        self.state().quit_acknowledged
+    }
+
+    /// Makes the next call to `reinit` fail with the given error.
+    pub fn fail_next_reinit(&self, error: OpenVRError) {
+        self.state().reinit_errors.push_back(error);
+    }
+
+    /// Adds a render model that becomes available after being polled `pending_polls` times.
+    pub fn add_render_model(&self, name: &str, model: RenderModel, pending_polls: u32) {
+        self.state().render_models.insert(
+            CString::new(name).unwrap(),
+            MockLoad {
+                value: Ok(model),
+                pending_polls,
+            },
+        );
+    }
+
+    /// Makes loading the named render model fail.
+    pub fn fail_render_model(&self, name: &str, error: &str) {
+        self.state().render_models.insert(
+            CString::new(name).unwrap(),
+            MockLoad {
+                value: Err(RenderModelError(error.into())),
+                pending_polls: 0,
+            },
+        );
+    }
+
+    /// Adds a component to a render model. The component itself is loaded as a render model by
+    /// its name, so it should be added with `add_render_model` as well.
+    pub fn add_render_model_component(&self, model_name: &str, component_name: &str) {
+        self.state()
+            .components
+            .entry(CString::new(model_name).unwrap())
+            .or_insert_with(Vec::new)
+            .push(CString::new(component_name).unwrap());
+    }
+
+    /// Sets the state of a render model component of a device, regardless of the model it uses.
+    pub fn set_component_state(&self, index: u32, component_name: &str, state: ComponentState) {
+        self.state()
+            .component_states
+            .insert((index, CString::new(component_name).unwrap()), state);
+    }
+
+    pub fn add_texture(&self, id: TextureId, texture: RenderModelTexture, pending_polls: u32) {
+        self.state().textures.insert(
+            id,
+            MockLoad {
+                value: Ok(texture),
+                pending_polls,
+            },
+        );
+    }
+
+    pub fn set_hidden_area_mesh(
+        &self,
+        eye: Eye,
+        mesh_type: HiddenAreaMeshType,
+        mesh: Vec<[f32; 2]>,
+    ) {
+        self.state()
+            .hidden_area_meshes
+            .insert((eye == Eye::Left, mesh_type as u32), mesh);
+    }
+
+    pub fn set_chaperone(
+        &self,
+        calibrated: bool,
+        play_area_rect: Option<[[f32; 3]; 4]>,
+        collision_bounds: Option<Vec<[f32; 3]>>,
+    ) {
+        let mut state = self.state();
+        state.chaperone_calibrated = calibrated;
+        state.play_area_rect = play_area_rect;
+        state.collision_bounds = collision_bounds;
+    }
+}
+
+impl MockState {
+    fn input_handle(&mut self, name: &CStr) -> u64 {
+        let next_handle = self.input_handles.len() as u64 + 1;
+        *self
+            .input_handles
+            .entry(name.to_owned())
+            .or_insert(next_handle)
+    }
+
+    fn check_input(&self) -> StdResult<(), InputError> {
+        if self.input_available {
+            Ok(())
+        } else {
+            Err(InputError::Unavailable)
+        }
+    }
+
+    fn action_state(&self, action: ActionHandle) -> StdResult<Option<ActionState>, InputError> {
+        self.check_input()?;
+        Ok(self.action_states.get(&action).cloned())
+    }
+}
+
+impl Runtime for MockRuntime {
+    fn is_initialized(&self) -> bool {
+        !self.state().shut_down
+    }
+
+    fn shutdown(&mut self) {
+        self.state().shut_down = true;
+    }
+
+    fn reinit(&mut self) -> StdResult<(), OpenVRError> {
+        let mut state = self.state();
+
+        if let Some(error) = state.reinit_errors.pop_front() {
+            state.shut_down = true;
+            return Err(error);
+        }
+
+        state.shut_down = false;
+        Ok(())
+    }
+
+    fn wait_get_poses(&mut self) -> StdResult<FramePoses, CompositorError> {
+        let mut state = self.state();
+
+        if let Some(error) = state.wait_errors.pop_front() {
+            return Err(error);
+        }
+
+        let devices = (0..sys::k_unMaxTrackedDeviceCount).map(|i| state.devices.get(&i));
+        Ok(FramePoses {
+            render: devices
+                .clone()
+                .map(|device| device.map(|device| device.pose).unwrap_or_default())
+                .collect(),
+            game: devices
+                .map(|device| {
+                    device
+                        .map(|device| device.game_pose.unwrap_or(device.pose))
+                        .unwrap_or_default()
+                }).collect(),
+        })
+    }
+
+    fn poll_next_event(&mut self, _origin: TrackingUniverseOrigin) -> Option<EventInfo> {
+        self.state().events.pop_front()
+    }
+
+    /// Extrapolates the position of every device linearly using its velocity.
+    fn device_to_absolute_tracking_pose(
+        &self,
+        _origin: TrackingUniverseOrigin,
+        predicted_seconds: f32,
+    ) -> Vec<DevicePose> {
+        let state = self.state();
+        (0..sys::k_unMaxTrackedDeviceCount)
+            .map(|i| {
+                let mut pose = state
+                    .devices
+                    .get(&i)
+                    .map(|device| device.pose)
+                    .unwrap_or_default();
+                for axis in 0..3 {
+                    pose.device_to_absolute_tracking[axis][3] +=
+                        pose.velocity[axis] * predicted_seconds;
+                }
+                pose
+            }).collect()
+    }
+
+    fn time_since_last_vsync(&self) -> Option<f32> {
+        Some(self.state().time_since_last_vsync)
+    }
+
+    fn submit(&mut self, eye: Eye, gl_target: usize) -> StdResult<(), CompositorError> {
+        self.state().submitted.push((eye, gl_target));
+        Ok(())
+    }
+
+    fn acknowledge_quit_exiting(&mut self) {
+        self.state().quit_acknowledged = true;
+    }
+
+    fn set_tracking_space(&mut self, origin: TrackingUniverseOrigin) {
+        self.state().tracking_space = Some(origin);
+    }
+
+    fn seated_zero_pose_to_standing(&self) -> [[f32; 4]; 3] {
+        self.state()
+            .seated_zero_pose
+            .unwrap_or(DevicePose::default().device_to_absolute_tracking)
+    }
+
+    fn reset_seated_zero_pose(&mut self) {
+        let mut state = self.state();
+        let hmd_pose = state
+            .devices
+            .get(&0)
+            .map(|device| device.pose.device_to_absolute_tracking);
+        if let Some(pose) = hmd_pose {
+            state.seated_zero_pose = Some(pose);
+        }
+        state.events.push_back(EventInfo {
+            tracked_device_index: sys::k_unTrackedDeviceIndex_Hmd,
+            age: 0.0,
+            event: Event::SeatedZeroPoseReset(SeatedZeroPoseReset {
+                reset_by_system_menu: false,
+            }),
+        });
+    }
+
+    fn max_tracked_device_count(&self) -> u32 {
+        sys::k_unMaxTrackedDeviceCount
+    }
+
+    fn tracked_device_class(&self, index: u32) -> TrackedDeviceClass {
+        self.state()
+            .devices
+            .get(&index)
+            .map(|device| device.class)
+            .unwrap_or(TrackedDeviceClass::Invalid)
+    }
+
+    fn controller_role(&self, index: u32) -> ControllerRole {
+        self.state()
+            .devices
+            .get(&index)
+            .map_or(ControllerRole::Invalid, |device| device.role)
+    }
+
+    fn controller_state(&self, index: u32) -> Option<ControllerState> {
+        self.state()
+            .devices
+            .get(&index)
+            .and_then(|device| device.controller_state)
+    }
+
+    fn controller_state_with_pose(
+        &self,
+        index: u32,
+        _origin: TrackingUniverseOrigin,
+    ) -> Option<(ControllerState, DevicePose)> {
+        self.state()
+            .devices
+            .get(&index)
+            .and_then(|device| device.controller_state.map(|state| (state, device.pose)))
+    }
+
+    fn trigger_haptic_pulse(&mut self, index: u32, axis: u32, duration_micros: u16) {
+        self.state()
+            .haptic_pulses
+            .push((index, axis, duration_micros));
+    }
+
+    fn string_tracked_device_property(
+        &self,
+        index: u32,
+        property: TrackedDeviceProperty,
+    ) -> Option<CString> {
+        match self.property(index, property) {
+            Some(MockProperty::String(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn float_tracked_device_property(
+        &self,
+        index: u32,
+        property: TrackedDeviceProperty,
+    ) -> Option<f32> {
+        match self.property(index, property) {
+            Some(MockProperty::Float(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn bool_tracked_device_property(
+        &self,
+        index: u32,
+        property: TrackedDeviceProperty,
+    ) -> Option<bool> {
+        match self.property(index, property) {
+            Some(MockProperty::Bool(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn eye_to_head_transform(&self, eye: Eye) -> [[f32; 4]; 3] {
+        // 64mm IPD
+        let offset = if eye == Eye::Left { -0.032 } else { 0.032 };
+        [
+            [1.0, 0.0, 0.0, offset],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+        ]
+    }
+
+    fn projection_matrix(&self, _eye: Eye, near: f32, far: f32) -> [[f32; 4]; 4] {
+        // Symmetric 90° field of view
+        [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, far / (near - far), near * far / (near - far)],
+            [0.0, 0.0, -1.0, 0.0],
+        ]
+    }
+
+    fn recommended_render_target_size(&self) -> (u32, u32) {
+        (1512, 1680)
+    }
+
+    fn hidden_area_mesh(&self, eye: Eye, mesh_type: HiddenAreaMeshType) -> Vec<[f32; 2]> {
+        self.state()
+            .hidden_area_meshes
+            .get(&(eye == Eye::Left, mesh_type as u32))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn load_render_model(&self, name: &CStr) -> RenderModelResult<RenderModel> {
+        match self.state().render_models.get_mut(name) {
+            Some(load) => load.poll(),
+            None => Err(RenderModelError("Render model not found".into())),
+        }
+    }
+
+    fn load_texture(&self, id: TextureId) -> RenderModelResult<RenderModelTexture> {
+        match self.state().textures.get_mut(&id) {
+            Some(load) => load.poll(),
+            None => Err(RenderModelError("Texture not found".into())),
+        }
+    }
+
+    fn component_count(&self, name: &CStr) -> u32 {
+        self.state()
+            .components
+            .get(name)
+            .map_or(0, |components| components.len() as u32)
+    }
+
+    fn component_name(&self, name: &CStr, component: u32) -> Option<CString> {
+        self.state()
+            .components
+            .get(name)
+            .and_then(|components| components.get(component as usize).cloned())
+    }
+
+    /// Components without a scripted state are visible at the device's origin.
+    fn component_state(
+        &self,
+        index: u32,
+        model_name: &CStr,
+        component_name: &CStr,
+        _controller_state: Option<&ControllerState>,
+    ) -> Option<ComponentState> {
+        let state = self.state();
+        let has_component = state
+            .components
+            .get(model_name)
+            .map_or(false, |components| {
+                components.iter().any(|name| name.as_c_str() == component_name)
+            });
+        if !has_component {
+            return None;
+        }
+
+        Some(
+            state
+                .component_states
+                .get(&(index, component_name.to_owned()))
+                .cloned()
+                .unwrap_or_default(),
+        )
+    }
+
+    fn is_chaperone_calibrated(&self) -> bool {
+        self.state().chaperone_calibrated
+    }
+
+    fn play_area_rect(&self) -> Option<[[f32; 3]; 4]> {
+        self.state().play_area_rect
+    }
+
+    fn collision_bounds(&self) -> Option<Vec<[f32; 3]>> {
+        self.state().collision_bounds.clone()
+    }
+
+    fn set_action_manifest_path(&mut self, path: &CStr) -> StdResult<(), InputError> {
+        let mut state = self.state();
+        state.check_input()?;
+        state.action_manifest_path = Some(path.to_owned());
+        Ok(())
+    }
+
+    fn action_set_handle(&self, name: &CStr) -> StdResult<ActionSetHandle, InputError> {
+        let mut state = self.state();
+        state.check_input()?;
+        Ok(state.input_handle(name))
+    }
+
+    fn action_handle(&self, name: &CStr) -> StdResult<ActionHandle, InputError> {
+        let mut state = self.state();
+        state.check_input()?;
+        Ok(state.input_handle(name))
+    }
+
+    fn update_action_state(&mut self, sets: &[ActionSetHandle]) -> StdResult<(), InputError> {
+        let mut state = self.state();
+        state.check_input()?;
+        state.updated_action_sets = sets.to_vec();
+        Ok(())
+    }
+
+    fn digital_action_data(
+        &self,
+        action: ActionHandle,
+    ) -> StdResult<DigitalActionData, InputError> {
+        match self.state().action_state(action)? {
+            Some(ActionState::Digital(data)) => Ok(data),
+            _ => Ok(DigitalActionData::default()),
+        }
+    }
+
+    fn analog_action_data(&self, action: ActionHandle) -> StdResult<AnalogActionData, InputError> {
+        match self.state().action_state(action)? {
+            Some(ActionState::Analog(data)) => Ok(data),
+            _ => Ok(AnalogActionData::default()),
+        }
+    }
+
+    fn pose_action_data(
+        &self,
+        action: ActionHandle,
+        _origin: TrackingUniverseOrigin,
+        _predicted_seconds: f32,
+    ) -> StdResult<PoseActionData, InputError> {
+        match self.state().action_state(action)? {
+            Some(ActionState::Pose(data)) => Ok(data),
+            _ => Ok(PoseActionData::default()),
+        }
+    }
+
+    fn skeletal_action_data(
+        &self,
+        action: ActionHandle,
+    ) -> StdResult<SkeletalActionData, InputError> {
+        match self.state().action_state(action)? {
+            Some(ActionState::Skeletal(data)) => Ok(data),
+            _ => Ok(SkeletalActionData::default()),
+        }
+    }
+
+    fn trigger_haptic_vibration_action(
+        &mut self,
+        action: ActionHandle,
+        duration: f32,
+        frequency: f32,
+        amplitude: f32,
+    ) -> StdResult<(), InputError> {
+        let mut state = self.state();
+        state.check_input()?;
+        state
+            .haptic_vibrations
+            .push((action, duration, frequency, amplitude));
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+//! The OpenVR runtime calls the backend is built on, abstracted so the backend can be driven
+//! without a headset.
+
+mod chaperone;
+mod interface;
+pub mod mock;
+mod native;
+
+pub use self::native::OpenVRRuntime;
+
+use std::ffi::{CStr, CString};
+use std::result::Result as StdResult;
+
+use openvr::render_models::Vertex;
+use openvr::system::event::EventInfo;
+use openvr::system::HiddenAreaMeshType;
+use openvr::{Eye, TrackedDeviceClass, TrackingUniverseOrigin};
+use openvr_sys as sys;
+
+use controller::ControllerState;
+use error::OpenVRError;
+use input::{
+    ActionHandle, ActionSetHandle, AnalogActionData, DigitalActionData, InputError,
+    PoseActionData, SkeletalActionData,
+};
+use properties::ControllerRole;
+
+pub type TextureId = sys::TextureID_t;
+pub type TrackedDeviceProperty = sys::ETrackedDeviceProperty;
+
+/// `TrackingResult_Fallback_RotationOnly` from `ETrackingResult` in openvr.h, matched by value
+/// so it doesn't depend on the OpenVR headers openvr_sys was generated from.
+const TRACKING_RESULT_FALLBACK_ROTATION_ONLY: sys::ETrackingResult = 300;
+
+/// The runtime calls used by the `OpenVR` backend.
+pub trait Runtime: Send + 'static {
+    /// Whether the runtime is connected. No other method may be called while it isn't.
+    fn is_initialized(&self) -> bool;
+    /// Disconnects from the runtime, does nothing if it already is.
+    fn shutdown(&mut self);
+    /// Connects to the runtime again after `shutdown`, e.g. once SteamVR was restarted.
+    fn reinit(&mut self) -> StdResult<(), OpenVRError>;
+
+    /// Blocks until the compositor is ready for the next frame and returns the poses for it.
+    fn wait_get_poses(&mut self) -> StdResult<FramePoses, CompositorError>;
+    fn poll_next_event(&mut self, origin: TrackingUniverseOrigin) -> Option<EventInfo>;
+    /// Poses predicted `predicted_seconds` from now, independent of the compositor's frame.
+    fn device_to_absolute_tracking_pose(
+        &self,
+        origin: TrackingUniverseOrigin,
+        predicted_seconds: f32,
+    ) -> Vec<DevicePose>;
+    fn time_since_last_vsync(&self) -> Option<f32>;
+    fn submit(&mut self, eye: Eye, gl_target: usize) -> StdResult<(), CompositorError>;
+    /// Tells the runtime the application is exiting after it requested a quit.
+    fn acknowledge_quit_exiting(&mut self);
+    /// Sets the origin the compositor reports poses relative to.
+    fn set_tracking_space(&mut self, origin: TrackingUniverseOrigin);
+    /// The seated zero pose relative to the standing origin.
+    fn seated_zero_pose_to_standing(&self) -> [[f32; 4]; 3];
+    /// Makes the current HMD pose the seated zero pose.
+    fn reset_seated_zero_pose(&mut self);
+
+    /// The number of device indices poses are reported for.
+    fn max_tracked_device_count(&self) -> u32;
+    fn tracked_device_class(&self, index: u32) -> TrackedDeviceClass;
+    fn controller_role(&self, index: u32) -> ControllerRole;
+    /// `None` if the device isn't a controller.
+    fn controller_state(&self, index: u32) -> Option<ControllerState>;
+    /// The controller state along with the pose of the device when the state was sampled.
+    fn controller_state_with_pose(
+        &self,
+        index: u32,
+        origin: TrackingUniverseOrigin,
+    ) -> Option<(ControllerState, DevicePose)>;
+    /// Legacy haptic pulse on one of the device's axes, at most 3999µs long.
+    fn trigger_haptic_pulse(&mut self, index: u32, axis: u32, duration_micros: u16);
+    fn string_tracked_device_property(
+        &self,
+        index: u32,
+        property: TrackedDeviceProperty,
+    ) -> Option<CString>;
+    fn float_tracked_device_property(
+        &self,
+        index: u32,
+        property: TrackedDeviceProperty,
+    ) -> Option<f32>;
+    fn bool_tracked_device_property(
+        &self,
+        index: u32,
+        property: TrackedDeviceProperty,
+    ) -> Option<bool>;
+
+    fn eye_to_head_transform(&self, eye: Eye) -> [[f32; 4]; 3];
+    fn projection_matrix(&self, eye: Eye, near: f32, far: f32) -> [[f32; 4]; 4];
+    fn recommended_render_target_size(&self) -> (u32, u32);
+    /// The hidden area mesh in render target UV space, empty if there is none.
+    fn hidden_area_mesh(&self, eye: Eye, mesh_type: HiddenAreaMeshType) -> Vec<[f32; 2]>;
+
+    /// `Ok(None)` while the model is still loading.
+    fn load_render_model(&self, name: &CStr) -> RenderModelResult<RenderModel>;
+    fn load_texture(&self, id: TextureId) -> RenderModelResult<RenderModelTexture>;
+    fn component_count(&self, name: &CStr) -> u32;
+    fn component_name(&self, name: &CStr, component: u32) -> Option<CString>;
+    /// The state of a render model component given the input state of the device, `None` if the
+    /// model has no such component. Devices without input pass `None` as their state.
+    fn component_state(
+        &self,
+        index: u32,
+        model_name: &CStr,
+        component_name: &CStr,
+        controller_state: Option<&ControllerState>,
+    ) -> Option<ComponentState>;
+
+    /// Returns `false` if the play area isn't set up or tracking calibration was lost.
+    fn is_chaperone_calibrated(&self) -> bool;
+    fn play_area_rect(&self) -> Option<[[f32; 3]; 4]>;
+    fn collision_bounds(&self) -> Option<Vec<[f32; 3]>>;
+
+    fn set_action_manifest_path(&mut self, path: &CStr) -> StdResult<(), InputError>;
+    fn action_set_handle(&self, name: &CStr) -> StdResult<ActionSetHandle, InputError>;
+    fn action_handle(&self, name: &CStr) -> StdResult<ActionHandle, InputError>;
+    /// Updates the state of all actions in the given action sets.
+    fn update_action_state(&mut self, sets: &[ActionSetHandle]) -> StdResult<(), InputError>;
+    fn digital_action_data(&self, action: ActionHandle)
+        -> StdResult<DigitalActionData, InputError>;
+    fn analog_action_data(&self, action: ActionHandle) -> StdResult<AnalogActionData, InputError>;
+    fn pose_action_data(
+        &self,
+        action: ActionHandle,
+        origin: TrackingUniverseOrigin,
+        predicted_seconds: f32,
+    ) -> StdResult<PoseActionData, InputError>;
+    fn skeletal_action_data(
+        &self,
+        action: ActionHandle,
+    ) -> StdResult<SkeletalActionData, InputError>;
+    fn trigger_haptic_vibration_action(
+        &mut self,
+        action: ActionHandle,
+        duration: f32,
+        frequency: f32,
+        amplitude: f32,
+    ) -> StdResult<(), InputError>;
+}
+
+/// How well a device is currently being tracked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackingResult {
+    Uninitialized,
+    CalibratingInProgress,
+    CalibratingOutOfRange,
+    RunningOk,
+    /// The device is tracked, but outside of the tracked volume so its pose is unreliable.
+    RunningOutOfRange,
+    /// Only the rotation of the device is tracked, its position is estimated.
+    FallbackRotationOnly,
+}
+
+impl TrackingResult {
+    fn from_sys(result: sys::ETrackingResult) -> TrackingResult {
+        match result {
+            sys::ETrackingResult_TrackingResult_Calibrating_InProgress => {
+                TrackingResult::CalibratingInProgress
+            }
+            sys::ETrackingResult_TrackingResult_Calibrating_OutOfRange => {
+                TrackingResult::CalibratingOutOfRange
+            }
+            sys::ETrackingResult_TrackingResult_Running_OK => TrackingResult::RunningOk,
+            sys::ETrackingResult_TrackingResult_Running_OutOfRange => {
+                TrackingResult::RunningOutOfRange
+            }
+            TRACKING_RESULT_FALLBACK_ROTATION_ONLY => TrackingResult::FallbackRotationOnly,
+            _ => TrackingResult::Uninitialized,
+        }
+    }
+
+    pub fn is_calibrating(self) -> bool {
+        self == TrackingResult::CalibratingInProgress
+            || self == TrackingResult::CalibratingOutOfRange
+    }
+
+    pub fn is_out_of_range(self) -> bool {
+        self == TrackingResult::CalibratingOutOfRange || self == TrackingResult::RunningOutOfRange
+    }
+}
+
+/// The pose of a single tracked device, as returned by the runtime.
+#[derive(Clone, Copy, Debug)]
+pub struct DevicePose {
+    pub device_to_absolute_tracking: [[f32; 4]; 3],
+    pub velocity: [f32; 3],
+    pub angular_velocity: [f32; 3],
+    pub tracking_result: TrackingResult,
+    pub pose_is_valid: bool,
+    pub device_is_connected: bool,
+}
+
+impl Default for DevicePose {
+    fn default() -> Self {
+        DevicePose {
+            device_to_absolute_tracking: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+            ],
+            velocity: [0.0; 3],
+            angular_velocity: [0.0; 3],
+            tracking_result: TrackingResult::Uninitialized,
+            pose_is_valid: false,
+            device_is_connected: false,
+        }
+    }
+}
+
+impl<'a> From<&'a sys::TrackedDevicePose_t> for DevicePose {
+    fn from(pose: &'a sys::TrackedDevicePose_t) -> Self {
+        DevicePose {
+            device_to_absolute_tracking: pose.mDeviceToAbsoluteTracking.m,
+            velocity: pose.vVelocity.v,
+            angular_velocity: pose.vAngularVelocity.v,
+            tracking_result: TrackingResult::from_sys(pose.eTrackingResult),
+            pose_is_valid: pose.bPoseIsValid,
+            device_is_connected: pose.bDeviceIsConnected,
+        }
+    }
+}
+
+/// An `EVRCompositorError` reported by the compositor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompositorError(pub sys::EVRCompositorError);
+
+/// The poses returned by the compositor for the upcoming frame.
+#[derive(Clone, Debug, Default)]
+pub struct FramePoses {
+    /// Poses predicted for when the frame will be displayed.
+    pub render: Vec<DevicePose>,
+    /// Poses predicted one frame further, for use by gameplay logic.
+    pub game: Vec<DevicePose>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RenderModel {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u16>,
+    pub diffuse_texture_id: Option<TextureId>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RenderModelTexture {
+    pub data: Vec<u8>,
+    pub size: (u16, u16),
+}
+
+/// The state of a render model component, for animating buttons, triggers and thumbsticks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ComponentState {
+    /// Where to draw the component's render model relative to the device.
+    pub transform: [[f32; 4]; 3],
+    /// The component doesn't move, so its state doesn't need to be queried again.
+    pub is_static: bool,
+    pub is_visible: bool,
+    pub is_touched: bool,
+    pub is_pressed: bool,
+    pub is_scrolled: bool,
+}
+
+impl Default for ComponentState {
+    fn default() -> Self {
+        ComponentState {
+            transform: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+            ],
+            is_static: false,
+            is_visible: true,
+            is_touched: false,
+            is_pressed: false,
+            is_scrolled: false,
+        }
+    }
+}
+
+impl<'a> From<&'a sys::RenderModel_ComponentState_t> for ComponentState {
+    fn from(state: &'a sys::RenderModel_ComponentState_t) -> Self {
+        let has = |property: u32| state.uProperties & property != 0;
+
+        ComponentState {
+            transform: state.mTrackingToComponentRenderModel.m,
+            is_static: has(sys::EVRComponentProperty_VRComponentProperty_IsStatic),
+            is_visible: has(sys::EVRComponentProperty_VRComponentProperty_IsVisible),
+            is_touched: has(sys::EVRComponentProperty_VRComponentProperty_IsTouched),
+            is_pressed: has(sys::EVRComponentProperty_VRComponentProperty_IsPressed),
+            is_scrolled: has(sys::EVRComponentProperty_VRComponentProperty_IsScrolled),
+        }
+    }
+}
+
+/// Description of an error reported by the runtime while loading a render model or texture.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderModelError(pub String);
+
+pub type RenderModelResult<T> = StdResult<Option<T>, RenderModelError>;
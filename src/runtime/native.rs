@@ -0,0 +1,834 @@
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::os::raw::c_char;
+use std::ptr;
+use std::result::Result as StdResult;
+use std::slice;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use openvr::render_models::Vertex;
+use openvr::system::event::EventInfo;
+use openvr::system::HiddenAreaMeshType;
+use openvr::{ApplicationType, Eye, TrackedDeviceClass, TrackingUniverseOrigin};
+use openvr_sys as sys;
+
+use controller::ControllerState;
+use error::OpenVRError;
+use input::{
+    ActionHandle, ActionSetHandle, AnalogActionData, BoneTransform, DigitalActionData,
+    InputError, PoseActionData, SkeletalActionData, HAND_BONE_COUNT,
+};
+use properties::ControllerRole;
+
+use super::chaperone::Chaperone;
+use super::interface::load_interface;
+use super::{
+    ComponentState, CompositorError, DevicePose, FramePoses, RenderModel, RenderModelError,
+    RenderModelResult, RenderModelTexture, Runtime, TextureId, TrackedDeviceProperty,
+};
+
+/// Set while an OpenVR context exists, the runtime only supports one per process.
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// Claims `INITIALIZED` until dropped.
+struct InstanceGuard;
+
+impl InstanceGuard {
+    fn acquire() -> StdResult<InstanceGuard, OpenVRError> {
+        if INITIALIZED.swap(true, Ordering::SeqCst) {
+            Err(OpenVRError::AlreadyInitialized)
+        } else {
+            Ok(InstanceGuard)
+        }
+    }
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        INITIALIZED.store(false, Ordering::SeqCst);
+    }
+}
+
+/// An OpenVR context, shut down when dropped.
+///
+/// It's created through openvr_sys rather than `openvr::init`, since openvr claims a global flag
+/// of its own before initializing and keeps it when initialization fails. Any later attempt
+/// would panic, while the runtime cleans up after a failed `VR_InitInternal` so it can simply be
+/// called again, e.g. once SteamVR is running. `InstanceGuard` is released on failure as well.
+struct Context;
+
+impl Context {
+    fn init(application_type: ApplicationType) -> StdResult<Context, OpenVRError> {
+        let mut error = sys::EVRInitError_VRInitError_None;
+        // `ApplicationType`'s discriminants are the `EVRApplicationType` values
+        unsafe { sys::VR_InitInternal(&mut error, application_type as sys::EVRApplicationType) };
+
+        if error == sys::EVRInitError_VRInitError_None {
+            Ok(Context)
+        } else {
+            Err(OpenVRError::from(error))
+        }
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        unsafe { sys::VR_ShutdownInternal() };
+    }
+}
+
+/// Everything loaded from one OpenVR context.
+///
+/// The interfaces are only valid while the context is alive. Fields are dropped in declaration
+/// order, so `context` has to stay after everything loaded from it, and the guard after the
+/// context so a new one can't be created before the old one has shut down.
+struct Session {
+    chaperone: Chaperone,
+    system_table: &'static sys::VR_IVRSystem_FnTable,
+    compositor_table: &'static sys::VR_IVRCompositor_FnTable,
+    render_models_table: &'static sys::VR_IVRRenderModels_FnTable,
+    /// Only available on runtimes supporting SteamVR Input.
+    input_table: Option<&'static sys::VR_IVRInput_FnTable>,
+    _context: Context,
+    _guard: InstanceGuard,
+}
+
+impl Session {
+    fn init(application_type: ApplicationType) -> StdResult<Session, OpenVRError> {
+        let guard = InstanceGuard::acquire()?;
+        // The guard makes sure this is the only context, and `Session` makes sure nothing loaded
+        // from it outlives it
+        let context = Context::init(application_type)?;
+        let chaperone =
+            Chaperone::new().ok_or(OpenVRError::InterfaceUnavailable("IVRChaperone"))?;
+        let system_table = unsafe {
+            load_interface::<sys::VR_IVRSystem_FnTable>(sys::IVRSystem_Version)
+                .ok_or(OpenVRError::InterfaceUnavailable("IVRSystem"))?
+        };
+        let compositor_table = unsafe {
+            load_interface::<sys::VR_IVRCompositor_FnTable>(sys::IVRCompositor_Version)
+                .ok_or(OpenVRError::InterfaceUnavailable("IVRCompositor"))?
+        };
+        let render_models_table = unsafe {
+            load_interface::<sys::VR_IVRRenderModels_FnTable>(sys::IVRRenderModels_Version)
+                .ok_or(OpenVRError::InterfaceUnavailable("IVRRenderModels"))?
+        };
+        let input_table =
+            unsafe { load_interface::<sys::VR_IVRInput_FnTable>(sys::IVRInput_Version) };
+
+        Ok(Session {
+            chaperone,
+            system_table,
+            compositor_table,
+            render_models_table,
+            input_table,
+            _context: context,
+            _guard: guard,
+        })
+    }
+}
+
+/// The actual OpenVR runtime, called through the function tables of its interfaces.
+///
+/// Only one can be initialized at a time. It shuts OpenVR down when dropped or through
+/// `Runtime::shutdown`, after which `Runtime::reinit` can connect to the runtime again, e.g.
+/// once SteamVR was restarted.
+pub struct OpenVRRuntime {
+    application_type: ApplicationType,
+    /// Applied again by `reinit`, since the new context doesn't know about it.
+    action_manifest_path: Option<CString>,
+    session: Option<Session>,
+}
+
+impl OpenVRRuntime {
+    pub fn init(application_type: ApplicationType) -> StdResult<OpenVRRuntime, OpenVRError> {
+        Ok(OpenVRRuntime {
+            application_type,
+            action_manifest_path: None,
+            session: Some(Session::init(application_type)?),
+        })
+    }
+
+    /// The current session. The `OpenVR` backend doesn't call into the runtime while it's shut
+    /// down, so this not being there is a bug.
+    fn session(&self) -> &Session {
+        self.session
+            .as_ref()
+            .expect("OpenVR runtime used while shut down")
+    }
+
+    fn set_action_manifest_path_inner(&self, path: &CStr) -> StdResult<(), InputError> {
+        let input = self.input()?;
+        check_input_error(unsafe { input.SetActionManifestPath.unwrap()(path.as_ptr() as *mut _) })
+    }
+
+    fn input(&self) -> StdResult<&'static sys::VR_IVRInput_FnTable, InputError> {
+        self.session().input_table.ok_or(InputError::Unavailable)
+    }
+}
+
+fn check_input_error(error: sys::EVRInputError) -> StdResult<(), InputError> {
+    if error == sys::EVRInputError_VRInputError_None {
+        Ok(())
+    } else {
+        Err(InputError::Runtime(error))
+    }
+}
+
+fn check_property<T>(value: T, error: sys::ETrackedPropertyError) -> Option<T> {
+    if error == sys::ETrackedPropertyError_TrackedProp_Success {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Reads a string through a getter that writes it into a buffer of the given size and returns
+/// the size it needs including the terminator, or 0 if there is no such string.
+fn read_string<F: FnMut(*mut c_char, u32) -> u32>(mut get: F) -> Option<CString> {
+    let size = get(ptr::null_mut(), 0);
+    if size == 0 {
+        return None;
+    }
+
+    let mut buffer = vec![0u8; size as usize];
+    let written = get(buffer.as_mut_ptr() as *mut c_char, size);
+    if written == 0 || written > size {
+        return None;
+    }
+    buffer.truncate(written as usize - 1);
+    CString::new(buffer).ok()
+}
+
+fn render_model_error(
+    table: &sys::VR_IVRRenderModels_FnTable,
+    error: sys::EVRRenderModelError,
+) -> RenderModelError {
+    let name = unsafe { CStr::from_ptr(table.GetRenderModelErrorNameFromEnum.unwrap()(error)) };
+    RenderModelError(name.to_string_lossy().into_owned())
+}
+
+fn check_compositor_error(error: sys::EVRCompositorError) -> StdResult<(), CompositorError> {
+    if error == sys::EVRCompositorError_VRCompositorError_None {
+        Ok(())
+    } else {
+        Err(CompositorError(error))
+    }
+}
+
+/// Poses of every device index, to be filled in by the runtime.
+fn pose_array() -> Vec<sys::TrackedDevicePose_t> {
+    vec![unsafe { mem::zeroed() }; sys::k_unMaxTrackedDeviceCount as usize]
+}
+
+fn sys_eye(eye: Eye) -> sys::EVREye {
+    match eye {
+        Eye::Left => sys::EVREye_Eye_Left,
+        Eye::Right => sys::EVREye_Eye_Right,
+    }
+}
+
+fn sys_mesh_type(mesh_type: HiddenAreaMeshType) -> sys::EHiddenAreaMeshType {
+    match mesh_type {
+        HiddenAreaMeshType::Standard => sys::EHiddenAreaMeshType_k_eHiddenAreaMesh_Standard,
+        HiddenAreaMeshType::Inverse => sys::EHiddenAreaMeshType_k_eHiddenAreaMesh_Inverse,
+        HiddenAreaMeshType::LineLoop => sys::EHiddenAreaMeshType_k_eHiddenAreaMesh_LineLoop,
+    }
+}
+
+fn sys_origin(origin: TrackingUniverseOrigin) -> sys::ETrackingUniverseOrigin {
+    match origin {
+        TrackingUniverseOrigin::Seated => sys::ETrackingUniverseOrigin_TrackingUniverseSeated,
+        TrackingUniverseOrigin::Standing => sys::ETrackingUniverseOrigin_TrackingUniverseStanding,
+        TrackingUniverseOrigin::RawAndUncalibrated => {
+            sys::ETrackingUniverseOrigin_TrackingUniverseRawAndUncalibrated
+        }
+    }
+}
+
+impl Runtime for OpenVRRuntime {
+    fn is_initialized(&self) -> bool {
+        self.session.is_some()
+    }
+
+    fn shutdown(&mut self) {
+        self.session = None;
+    }
+
+    fn reinit(&mut self) -> StdResult<(), OpenVRError> {
+        // The old context has to be gone before a new one can be created
+        self.session = None;
+        self.session = Some(Session::init(self.application_type)?);
+
+        if let Some(ref path) = self.action_manifest_path {
+            if let Err(e) = self.set_action_manifest_path_inner(path) {
+                warn!("Failed to restore SteamVR Input action manifest: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn wait_get_poses(&mut self) -> StdResult<FramePoses, CompositorError> {
+        // The raw poses, as openvr's can't represent every tracking result
+        let mut render = pose_array();
+        let mut game = pose_array();
+        check_compositor_error(unsafe {
+            self.session().compositor_table.WaitGetPoses.unwrap()(
+                render.as_mut_ptr(),
+                render.len() as u32,
+                game.as_mut_ptr(),
+                game.len() as u32,
+            )
+        })?;
+
+        Ok(FramePoses {
+            render: render.iter().map(DevicePose::from).collect(),
+            game: game.iter().map(DevicePose::from).collect(),
+        })
+    }
+
+    fn poll_next_event(&mut self, origin: TrackingUniverseOrigin) -> Option<EventInfo> {
+        let mut event: sys::VREvent_t = unsafe { mem::zeroed() };
+        let mut pose: sys::TrackedDevicePose_t = unsafe { mem::zeroed() };
+        let available = unsafe {
+            self.session().system_table.PollNextEventWithPose.unwrap()(
+                sys_origin(origin),
+                &mut event,
+                mem::size_of::<sys::VREvent_t>() as u32,
+                &mut pose,
+            )
+        };
+
+        if available {
+            Some(EventInfo::from(event))
+        } else {
+            None
+        }
+    }
+
+    fn device_to_absolute_tracking_pose(
+        &self,
+        origin: TrackingUniverseOrigin,
+        predicted_seconds: f32,
+    ) -> Vec<DevicePose> {
+        let system_table = self.session().system_table;
+        let mut poses = pose_array();
+        unsafe {
+            system_table.GetDeviceToAbsoluteTrackingPose.unwrap()(
+                sys_origin(origin),
+                predicted_seconds,
+                poses.as_mut_ptr(),
+                poses.len() as u32,
+            )
+        };
+
+        poses.iter().map(DevicePose::from).collect()
+    }
+
+    fn time_since_last_vsync(&self) -> Option<f32> {
+        let mut seconds = 0.0;
+        let mut frame_counter = 0;
+        let valid = unsafe {
+            self.session().system_table.GetTimeSinceLastVsync.unwrap()(
+                &mut seconds,
+                &mut frame_counter,
+            )
+        };
+
+        if valid {
+            Some(seconds)
+        } else {
+            None
+        }
+    }
+
+    fn submit(&mut self, eye: Eye, gl_target: usize) -> StdResult<(), CompositorError> {
+        let mut texture = sys::Texture_t {
+            handle: gl_target as *mut _,
+            eType: sys::ETextureType_TextureType_OpenGL,
+            eColorSpace: sys::EColorSpace_ColorSpace_Linear,
+        };
+        check_compositor_error(unsafe {
+            self.session().compositor_table.Submit.unwrap()(
+                sys_eye(eye),
+                &mut texture,
+                ptr::null_mut(),
+                sys::EVRSubmitFlags_Submit_Default,
+            )
+        })
+    }
+
+    fn acknowledge_quit_exiting(&mut self) {
+        unsafe { self.session().system_table.AcknowledgeQuit_Exiting.unwrap()() };
+    }
+
+    fn set_tracking_space(&mut self, origin: TrackingUniverseOrigin) {
+        unsafe { self.session().compositor_table.SetTrackingSpace.unwrap()(sys_origin(origin)) };
+    }
+
+    fn seated_zero_pose_to_standing(&self) -> [[f32; 4]; 3] {
+        unsafe {
+            self.session()
+                .system_table
+                .GetSeatedZeroPoseToStandingAbsoluteTrackingPose
+                .unwrap()()
+                .m
+        }
+    }
+
+    fn reset_seated_zero_pose(&mut self) {
+        unsafe { self.session().system_table.ResetSeatedZeroPose.unwrap()() };
+    }
+
+    fn max_tracked_device_count(&self) -> u32 {
+        sys::k_unMaxTrackedDeviceCount
+    }
+
+    fn tracked_device_class(&self, index: u32) -> TrackedDeviceClass {
+        let class = unsafe { self.session().system_table.GetTrackedDeviceClass.unwrap()(index) };
+        match class {
+            sys::ETrackedDeviceClass_TrackedDeviceClass_HMD => TrackedDeviceClass::HMD,
+            sys::ETrackedDeviceClass_TrackedDeviceClass_Controller => {
+                TrackedDeviceClass::Controller
+            }
+            sys::ETrackedDeviceClass_TrackedDeviceClass_GenericTracker => {
+                TrackedDeviceClass::GenericTracker
+            }
+            sys::ETrackedDeviceClass_TrackedDeviceClass_TrackingReference => {
+                TrackedDeviceClass::TrackingReference
+            }
+            sys::ETrackedDeviceClass_TrackedDeviceClass_DisplayRedirect => {
+                TrackedDeviceClass::DisplayRedirect
+            }
+            _ => TrackedDeviceClass::Invalid,
+        }
+    }
+
+    fn controller_role(&self, index: u32) -> ControllerRole {
+        let role = unsafe {
+            self.session()
+                .system_table
+                .GetControllerRoleForTrackedDeviceIndex
+                .unwrap()(index)
+        };
+        ControllerRole::from_sys(role)
+    }
+
+    fn controller_state(&self, index: u32) -> Option<ControllerState> {
+        let mut state: sys::VRControllerState_t = unsafe { mem::zeroed() };
+        let valid = unsafe {
+            self.session().system_table.GetControllerState.unwrap()(
+                index,
+                &mut state,
+                mem::size_of::<sys::VRControllerState_t>() as u32,
+            )
+        };
+
+        if valid {
+            Some(ControllerState::from_sys(&state))
+        } else {
+            None
+        }
+    }
+
+    fn controller_state_with_pose(
+        &self,
+        index: u32,
+        origin: TrackingUniverseOrigin,
+    ) -> Option<(ControllerState, DevicePose)> {
+        let mut state: sys::VRControllerState_t = unsafe { mem::zeroed() };
+        let mut pose: sys::TrackedDevicePose_t = unsafe { mem::zeroed() };
+        let valid = unsafe {
+            self.session().system_table.GetControllerStateWithPose.unwrap()(
+                sys_origin(origin),
+                index,
+                &mut state,
+                mem::size_of::<sys::VRControllerState_t>() as u32,
+                &mut pose,
+            )
+        };
+
+        if valid {
+            Some((ControllerState::from_sys(&state), DevicePose::from(&pose)))
+        } else {
+            None
+        }
+    }
+
+    fn trigger_haptic_pulse(&mut self, index: u32, axis: u32, duration_micros: u16) {
+        unsafe {
+            self.session().system_table.TriggerHapticPulse.unwrap()(index, axis, duration_micros)
+        };
+    }
+
+    fn string_tracked_device_property(
+        &self,
+        index: u32,
+        property: TrackedDeviceProperty,
+    ) -> Option<CString> {
+        let system_table = self.session().system_table;
+        let get = system_table.GetStringTrackedDeviceProperty.unwrap();
+        let mut error = sys::ETrackedPropertyError_TrackedProp_Success;
+        let value =
+            read_string(|buffer, size| unsafe { get(index, property, buffer, size, &mut error) });
+        value.and_then(|value| check_property(value, error))
+    }
+
+    fn float_tracked_device_property(
+        &self,
+        index: u32,
+        property: TrackedDeviceProperty,
+    ) -> Option<f32> {
+        let mut error = sys::ETrackedPropertyError_TrackedProp_Success;
+        let system_table = self.session().system_table;
+        let value = unsafe {
+            system_table.GetFloatTrackedDeviceProperty.unwrap()(index, property, &mut error)
+        };
+        check_property(value, error)
+    }
+
+    fn bool_tracked_device_property(
+        &self,
+        index: u32,
+        property: TrackedDeviceProperty,
+    ) -> Option<bool> {
+        let mut error = sys::ETrackedPropertyError_TrackedProp_Success;
+        let system_table = self.session().system_table;
+        let value = unsafe {
+            system_table.GetBoolTrackedDeviceProperty.unwrap()(index, property, &mut error)
+        };
+        check_property(value, error)
+    }
+
+    fn eye_to_head_transform(&self, eye: Eye) -> [[f32; 4]; 3] {
+        unsafe { self.session().system_table.GetEyeToHeadTransform.unwrap()(sys_eye(eye)).m }
+    }
+
+    fn projection_matrix(&self, eye: Eye, near: f32, far: f32) -> [[f32; 4]; 4] {
+        let system_table = self.session().system_table;
+        unsafe { system_table.GetProjectionMatrix.unwrap()(sys_eye(eye), near, far).m }
+    }
+
+    fn recommended_render_target_size(&self) -> (u32, u32) {
+        let system_table = self.session().system_table;
+        let (mut width, mut height) = (0, 0);
+        unsafe { system_table.GetRecommendedRenderTargetSize.unwrap()(&mut width, &mut height) };
+        (width, height)
+    }
+
+    fn hidden_area_mesh(&self, eye: Eye, mesh_type: HiddenAreaMeshType) -> Vec<[f32; 2]> {
+        let mesh = unsafe {
+            self.session().system_table.GetHiddenAreaMesh.unwrap()(
+                sys_eye(eye),
+                sys_mesh_type(mesh_type),
+            )
+        };
+        if mesh.pVertexData.is_null() {
+            return Vec::new();
+        }
+
+        // Line loops have one vertex per "triangle", triangle lists three
+        let vertex_count = match mesh_type {
+            HiddenAreaMeshType::LineLoop => mesh.unTriangleCount,
+            _ => mesh.unTriangleCount * 3,
+        };
+        unsafe { slice::from_raw_parts(mesh.pVertexData, vertex_count as usize) }
+            .iter()
+            .map(|vertex| vertex.v)
+            .collect()
+    }
+
+    fn load_render_model(&self, name: &CStr) -> RenderModelResult<RenderModel> {
+        let table = self.session().render_models_table;
+        let mut model: *mut sys::RenderModel_t = ptr::null_mut();
+        let error =
+            unsafe { table.LoadRenderModel_Async.unwrap()(name.as_ptr() as *mut _, &mut model) };
+        if error == sys::EVRRenderModelError_VRRenderModelError_Loading {
+            return Ok(None);
+        } else if error != sys::EVRRenderModelError_VRRenderModelError_None {
+            return Err(render_model_error(table, error));
+        }
+
+        // The runtime owns the model, so it's copied before being freed
+        let loaded = unsafe {
+            let model = &*model;
+            let vertices = slice::from_raw_parts(model.rVertexData, model.unVertexCount as usize);
+            let index_count = model.unTriangleCount as usize * 3;
+
+            RenderModel {
+                vertices: vertices
+                    .iter()
+                    .map(|vertex| Vertex {
+                        position: vertex.vPosition.v,
+                        normal: vertex.vNormal.v,
+                        texture_coord: vertex.rfTextureCoord,
+                    }).collect(),
+                indices: slice::from_raw_parts(model.rIndexData, index_count).to_vec(),
+                // Models without a texture have `INVALID_TEXTURE_ID`, which is -1
+                diffuse_texture_id: Some(model.diffuseTextureId).filter(|&id| id >= 0),
+            }
+        };
+        unsafe { table.FreeRenderModel.unwrap()(model) };
+
+        Ok(Some(loaded))
+    }
+
+    fn load_texture(&self, id: TextureId) -> RenderModelResult<RenderModelTexture> {
+        let table = self.session().render_models_table;
+        let mut texture: *mut sys::RenderModel_TextureMap_t = ptr::null_mut();
+        let error = unsafe { table.LoadTexture_Async.unwrap()(id, &mut texture) };
+        if error == sys::EVRRenderModelError_VRRenderModelError_Loading {
+            return Ok(None);
+        } else if error != sys::EVRRenderModelError_VRRenderModelError_None {
+            return Err(render_model_error(table, error));
+        }
+
+        let loaded = unsafe {
+            let texture = &*texture;
+            let size = (texture.unWidth, texture.unHeight);
+            // RGBA, 4 bytes per pixel
+            let byte_count = size.0 as usize * size.1 as usize * 4;
+
+            RenderModelTexture {
+                data: slice::from_raw_parts(texture.rubTextureMapData, byte_count).to_vec(),
+                size,
+            }
+        };
+        unsafe { table.FreeTexture.unwrap()(texture) };
+
+        Ok(Some(loaded))
+    }
+
+    fn component_count(&self, name: &CStr) -> u32 {
+        let table = self.session().render_models_table;
+        unsafe { table.GetComponentCount.unwrap()(name.as_ptr() as *mut _) }
+    }
+
+    fn component_name(&self, name: &CStr, component: u32) -> Option<CString> {
+        let get = self.session().render_models_table.GetComponentName.unwrap();
+        read_string(|buffer, size| unsafe { get(name.as_ptr() as *mut _, component, buffer, size) })
+    }
+
+    fn component_state(
+        &self,
+        _index: u32,
+        model_name: &CStr,
+        component_name: &CStr,
+        controller_state: Option<&ControllerState>,
+    ) -> Option<ComponentState> {
+        let session = self.session();
+
+        // A zeroed state is what the runtime expects for devices without input
+        let mut controller_state = match controller_state {
+            Some(state) => state.to_sys(),
+            None => unsafe { mem::zeroed() },
+        };
+
+        let mut mode: sys::RenderModel_ControllerMode_State_t = unsafe { mem::zeroed() };
+        let mut state: sys::RenderModel_ComponentState_t = unsafe { mem::zeroed() };
+        let valid = unsafe {
+            session.render_models_table.GetComponentState.unwrap()(
+                model_name.as_ptr() as *mut _,
+                component_name.as_ptr() as *mut _,
+                &mut controller_state,
+                &mut mode,
+                &mut state,
+            )
+        };
+
+        if valid {
+            Some(ComponentState::from(&state))
+        } else {
+            None
+        }
+    }
+
+    fn is_chaperone_calibrated(&self) -> bool {
+        self.session().chaperone.is_calibrated()
+    }
+
+    fn play_area_rect(&self) -> Option<[[f32; 3]; 4]> {
+        self.session().chaperone.play_area_rect()
+    }
+
+    fn collision_bounds(&self) -> Option<Vec<[f32; 3]>> {
+        self.session().chaperone.collision_bounds()
+    }
+
+    fn set_action_manifest_path(&mut self, path: &CStr) -> StdResult<(), InputError> {
+        self.set_action_manifest_path_inner(path)?;
+        self.action_manifest_path = Some(path.to_owned());
+        Ok(())
+    }
+
+    fn action_set_handle(&self, name: &CStr) -> StdResult<ActionSetHandle, InputError> {
+        let input = self.input()?;
+        let mut handle = 0;
+        check_input_error(unsafe {
+            input.GetActionSetHandle.unwrap()(name.as_ptr() as *mut _, &mut handle)
+        })?;
+        Ok(handle)
+    }
+
+    fn action_handle(&self, name: &CStr) -> StdResult<ActionHandle, InputError> {
+        let input = self.input()?;
+        let mut handle = 0;
+        check_input_error(unsafe {
+            input.GetActionHandle.unwrap()(name.as_ptr() as *mut _, &mut handle)
+        })?;
+        Ok(handle)
+    }
+
+    fn update_action_state(&mut self, sets: &[ActionSetHandle]) -> StdResult<(), InputError> {
+        let input = self.input()?;
+
+        let mut active_sets: Vec<sys::VRActiveActionSet_t> = sets
+            .iter()
+            .map(|&handle| {
+                let mut set: sys::VRActiveActionSet_t = unsafe { mem::zeroed() };
+                set.ulActionSet = handle;
+                set.ulRestrictedToDevice = sys::k_ulInvalidInputValueHandle;
+                set
+            }).collect();
+
+        check_input_error(unsafe {
+            input.UpdateActionState.unwrap()(
+                active_sets.as_mut_ptr(),
+                mem::size_of::<sys::VRActiveActionSet_t>() as u32,
+                active_sets.len() as u32,
+            )
+        })
+    }
+
+    fn digital_action_data(
+        &self,
+        action: ActionHandle,
+    ) -> StdResult<DigitalActionData, InputError> {
+        let input = self.input()?;
+        let mut data: sys::InputDigitalActionData_t = unsafe { mem::zeroed() };
+        check_input_error(unsafe {
+            input.GetDigitalActionData.unwrap()(
+                action,
+                &mut data,
+                mem::size_of::<sys::InputDigitalActionData_t>() as u32,
+                sys::k_ulInvalidInputValueHandle,
+            )
+        })?;
+
+        Ok(DigitalActionData {
+            active: data.bActive,
+            state: data.bState,
+            changed: data.bChanged,
+        })
+    }
+
+    fn analog_action_data(&self, action: ActionHandle) -> StdResult<AnalogActionData, InputError> {
+        let input = self.input()?;
+        let mut data: sys::InputAnalogActionData_t = unsafe { mem::zeroed() };
+        check_input_error(unsafe {
+            input.GetAnalogActionData.unwrap()(
+                action,
+                &mut data,
+                mem::size_of::<sys::InputAnalogActionData_t>() as u32,
+                sys::k_ulInvalidInputValueHandle,
+            )
+        })?;
+
+        Ok(AnalogActionData {
+            active: data.bActive,
+            value: [data.x, data.y, data.z],
+            delta: [data.deltaX, data.deltaY, data.deltaZ],
+        })
+    }
+
+    fn pose_action_data(
+        &self,
+        action: ActionHandle,
+        origin: TrackingUniverseOrigin,
+        predicted_seconds: f32,
+    ) -> StdResult<PoseActionData, InputError> {
+        let input = self.input()?;
+        let mut data: sys::InputPoseActionData_t = unsafe { mem::zeroed() };
+        check_input_error(unsafe {
+            input.GetPoseActionData.unwrap()(
+                action,
+                sys_origin(origin),
+                predicted_seconds,
+                &mut data,
+                mem::size_of::<sys::InputPoseActionData_t>() as u32,
+                sys::k_ulInvalidInputValueHandle,
+            )
+        })?;
+
+        Ok(PoseActionData {
+            active: data.bActive,
+            pose: DevicePose::from(&data.pose),
+        })
+    }
+
+    fn skeletal_action_data(
+        &self,
+        action: ActionHandle,
+    ) -> StdResult<SkeletalActionData, InputError> {
+        let input = self.input()?;
+        let mut data: sys::InputSkeletalActionData_t = unsafe { mem::zeroed() };
+        check_input_error(unsafe {
+            input.GetSkeletalActionData.unwrap()(
+                action,
+                &mut data,
+                mem::size_of::<sys::InputSkeletalActionData_t>() as u32,
+            )
+        })?;
+
+        if !data.bActive {
+            return Ok(SkeletalActionData::default());
+        }
+
+        let mut bones: Vec<sys::VRBoneTransform_t> =
+            vec![unsafe { mem::zeroed() }; HAND_BONE_COUNT];
+        check_input_error(unsafe {
+            input.GetSkeletalBoneData.unwrap()(
+                action,
+                sys::EVRSkeletalTransformSpace_VRSkeletalTransformSpace_Parent,
+                sys::EVRSkeletalMotionRange_VRSkeletalMotionRange_WithController,
+                bones.as_mut_ptr(),
+                bones.len() as u32,
+            )
+        })?;
+
+        Ok(SkeletalActionData {
+            active: true,
+            bones: bones
+                .iter()
+                .map(|bone| {
+                    let p = bone.position.v;
+                    let q = bone.orientation;
+                    BoneTransform {
+                        position: [p[0], p[1], p[2]],
+                        orientation: [q.w, q.x, q.y, q.z],
+                    }
+                }).collect(),
+        })
+    }
+
+    fn trigger_haptic_vibration_action(
+        &mut self,
+        action: ActionHandle,
+        duration: f32,
+        frequency: f32,
+        amplitude: f32,
+    ) -> StdResult<(), InputError> {
+        let input = self.input()?;
+        check_input_error(unsafe {
+            input.TriggerHapticVibrationAction.unwrap()(
+                action,
+                0.0,
+                duration,
+                frequency,
+                amplitude,
+                sys::k_ulInvalidInputValueHandle,
+            )
+        })
+    }
+}
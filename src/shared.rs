@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use controller::ControllerState;
+use event::OpenVREvent;
+use haptics::HapticRequest;
+use input::ActionState;
+use openvr::TrackingUniverseOrigin;
+use runtime::{DevicePose, TrackingResult};
+use properties::TrackerProperties;
+use render_models::{ComponentMaterial, RenderModelProgress};
+use tracking::TrackerComponentState;
+
+/// State shared between the backend, which is owned by amethyst's XR system, and the
+/// `OpenVRSystem` added by the `OpenVRBundle`.
+#[derive(Clone, Default)]
+pub(crate) struct Shared(Arc<Mutex<SharedState>>);
+
+#[derive(Default)]
+pub(crate) struct SharedState {
+    /// Only set once an `OpenVRSystem` exists, so events don't pile up without a consumer.
+    pub publish_events: bool,
+    pub events: Vec<OpenVREvent>,
+
+    pub trackers: HashMap<u32, TrackerProperties>,
+    pub trackers_changed: bool,
+    pub render_model_progress: HashMap<u32, RenderModelProgress>,
+    pub render_model_materials: HashMap<u32, Vec<ComponentMaterial>>,
+
+    pub controller_states: HashMap<u32, ControllerState>,
+    pub controller_poses: HashMap<u32, DevicePose>,
+
+    pub haptic_requests: Vec<HapticRequest>,
+
+    pub game_poses: Vec<DevicePose>,
+    pub seconds_to_photons: f32,
+    /// How far ahead to predict `predicted_poses`, requested through the `OpenVRTracking`
+    /// resource. Stays set until it's cleared there.
+    pub predicted_seconds: Option<f32>,
+    pub predicted_poses: Vec<DevicePose>,
+    pub tracking_results: Vec<TrackingResult>,
+    pub component_states: HashMap<u32, Vec<TrackerComponentState>>,
+
+    pub tracking_origin: Option<TrackingUniverseOrigin>,
+    /// Origin requested through the `OpenVRTracking` resource.
+    pub requested_tracking_origin: Option<TrackingUniverseOrigin>,
+    pub seated_to_standing: Option<[[f32; 4]; 3]>,
+    pub reset_seated_zero_pose_requested: bool,
+
+    pub action_states: HashMap<String, ActionState>,
+    /// Active action sets requested through the `OpenVRInput` resource.
+    pub requested_action_sets: Option<Vec<String>>,
+}
+
+impl SharedState {
+    pub fn push_event(&mut self, event: OpenVREvent) {
+        if self.publish_events {
+            self.events.push(event);
+        }
+    }
+}
+
+impl Shared {
+    pub fn lock(&self) -> MutexGuard<SharedState> {
+        self.0.lock().unwrap()
+    }
+}
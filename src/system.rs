@@ -0,0 +1,180 @@
+use std::hash::Hash;
+
+use amethyst::core::shrev::EventChannel;
+use amethyst::core::specs::prelude::*;
+use amethyst::input::InputEvent;
+
+use controller::OpenVRControllerStates;
+use event::OpenVREvent;
+use haptics::{HapticFeedback, HapticRequest};
+use input::OpenVRInput;
+use properties::OpenVRTrackers;
+use runtime::Runtime;
+use shared::Shared;
+use tracking::OpenVRTracking;
+use {array_to_matrix, extend_matrix_array, OpenVR};
+
+/// Publishes the data collected by the backend during `wait` to the world: events go to an
+/// `EventChannel<OpenVREvent>`, tracker properties to the `OpenVRTrackers` resource, legacy
+/// controller states to `OpenVRControllerStates` and action states to `OpenVRInput`. Haptic
+/// requests written to the `EventChannel<HapticRequest>` or `HapticFeedback` components and
+/// changes made through `OpenVRInput` or `OpenVRTracking` are passed back to the backend.
+pub struct OpenVRSystem {
+    shared: Shared,
+    haptic_reader: Option<ReaderId<HapticRequest>>,
+}
+
+impl OpenVRSystem {
+    /// Creates the system for a backend. Only needed when adding amethyst's `XRBundle` by hand,
+    /// the `OpenVRBundle` adds it already.
+    pub fn new<R: Runtime>(backend: &OpenVR<R>) -> OpenVRSystem {
+        let shared = backend.shared.clone();
+        shared.lock().publish_events = true;
+        OpenVRSystem {
+            shared,
+            haptic_reader: None,
+        }
+    }
+}
+
+impl<'a> System<'a> for OpenVRSystem {
+    type SystemData = (
+        Write<'a, EventChannel<OpenVREvent>>,
+        Write<'a, OpenVRTrackers>,
+        Write<'a, OpenVRControllerStates>,
+        Write<'a, OpenVRInput>,
+        Read<'a, EventChannel<HapticRequest>>,
+        Write<'a, OpenVRTracking>,
+        WriteStorage<'a, HapticFeedback>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut events,
+            mut trackers,
+            mut controllers,
+            mut input,
+            haptic_requests,
+            mut tracking,
+            mut haptic_feedback,
+        ): Self::SystemData,
+    ) {
+        let mut shared = self.shared.lock();
+
+        events.drain_vec_write(&mut shared.events);
+
+        if shared.trackers_changed {
+            trackers.properties = shared.trackers.clone();
+            trackers
+                .render_model_progress
+                .clone_from(&shared.render_model_progress);
+            trackers
+                .render_model_materials
+                .clone_from(&shared.render_model_materials);
+            shared.trackers_changed = false;
+        }
+
+        let states = shared.controller_states.clone();
+        controllers.previous_states = ::std::mem::replace(&mut controllers.states, states);
+        controllers.poses.clone_from(&shared.controller_poses);
+
+        input.actions.clone_from(&shared.action_states);
+        if let Some(sets) = input.requested_action_sets.take() {
+            shared.requested_action_sets = Some(sets);
+        }
+
+        shared
+            .haptic_requests
+            .extend(haptic_requests.read(self.haptic_reader.as_mut().unwrap()).cloned());
+        for feedback in (&mut haptic_feedback).join() {
+            shared.haptic_requests.extend(feedback.requests.drain(..));
+        }
+
+        tracking.game_poses.clone_from(&shared.game_poses);
+        tracking.seconds_to_photons = shared.seconds_to_photons;
+        tracking.predicted_poses.clone_from(&shared.predicted_poses);
+        shared.predicted_seconds = tracking.predicted_seconds;
+        tracking.tracking_results.clone_from(&shared.tracking_results);
+        tracking.component_states.clone_from(&shared.component_states);
+        if let Some(origin) = shared.tracking_origin {
+            tracking.origin = origin;
+        }
+        if let Some(origin) = tracking.requested_origin.take() {
+            shared.requested_tracking_origin = Some(origin);
+        }
+        if let Some(pose) = shared.seated_to_standing.take() {
+            tracking.seated_to_standing = array_to_matrix(extend_matrix_array(pose));
+        }
+        if tracking.reset_seated_zero_pose_requested {
+            tracking.reset_seated_zero_pose_requested = false;
+            shared.reset_seated_zero_pose_requested = true;
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+
+        self.haptic_reader = Some(
+            res.fetch_mut::<EventChannel<HapticRequest>>()
+                .register_reader(),
+        );
+    }
+}
+
+/// Forwards digital SteamVR Input actions to amethyst's input events as
+/// `InputEvent::ActionPressed` and `InputEvent::ActionReleased`, so code reacting to keyboard or
+/// gamepad actions reacts to VR controllers too.
+///
+/// amethyst's `InputHandler` derives action state from its own bindings only, so
+/// `InputHandler::action_is_down` doesn't see these actions. Use `OpenVRInput::action_is_down`
+/// for polling them instead. Add this system with a dependency on `"openvr_system"`.
+pub struct OpenVRActionEventSystem<AC> {
+    /// Action path, the amethyst action it's forwarded as and whether it was down last frame.
+    actions: Vec<(String, AC, bool)>,
+}
+
+impl<AC> OpenVRActionEventSystem<AC> {
+    pub fn new() -> OpenVRActionEventSystem<AC> {
+        OpenVRActionEventSystem {
+            actions: Vec::new(),
+        }
+    }
+
+    /// Forwards the digital action at `path`, e.g. `/actions/main/in/trigger`, as `action`. The
+    /// action has to be registered with `OpenVR::add_action`.
+    pub fn with_action(mut self, path: &str, action: AC) -> OpenVRActionEventSystem<AC> {
+        self.actions.push((path.to_owned(), action, false));
+        self
+    }
+}
+
+impl<AC> Default for OpenVRActionEventSystem<AC> {
+    fn default() -> Self {
+        OpenVRActionEventSystem::new()
+    }
+}
+
+impl<'a, AC> System<'a> for OpenVRActionEventSystem<AC>
+where
+    AC: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    type SystemData = (
+        Read<'a, OpenVRInput>,
+        Write<'a, EventChannel<InputEvent<AC>>>,
+    );
+
+    fn run(&mut self, (input, mut events): Self::SystemData) {
+        for &mut (ref path, ref action, ref mut was_down) in &mut self.actions {
+            let is_down = input.action_is_down(path).unwrap_or(false);
+            if is_down != *was_down {
+                events.single_write(if is_down {
+                    InputEvent::ActionPressed(action.clone())
+                } else {
+                    InputEvent::ActionReleased(action.clone())
+                });
+                *was_down = is_down;
+            }
+        }
+    }
+}
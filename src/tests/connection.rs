@@ -0,0 +1,109 @@
+use amethyst::xr::XRBackend;
+use openvr::TrackedDeviceClass;
+use openvr_sys as sys;
+
+use super::{assert_near, backend, new_trackers, quit, take_events, translated};
+use runtime::{CompositorError, DevicePose, TrackingResult};
+use {ActionKind, InputError, OpenVRError, OpenVREvent};
+
+#[test]
+fn init_errors_keep_their_code() {
+    let (runtime, mut openvr) = backend();
+    runtime.fail_next_reinit(OpenVRError::from(
+        sys::EVRInitError_VRInitError_Init_HmdNotFound,
+    ));
+
+    let error = openvr.restart().unwrap_err();
+    assert_eq!(
+        error.code(),
+        Some(sys::EVRInitError_VRInitError_Init_HmdNotFound)
+    );
+    assert_eq!(
+        error.symbol(),
+        Some("VRInitError_Init_HmdNotFound".to_owned())
+    );
+    assert_eq!(OpenVRError::AlreadyInitialized.code(), None);
+    assert_eq!(OpenVRError::InterfaceUnavailable("IVRInput").symbol(), None);
+}
+
+#[test]
+fn failed_restart_can_be_retried() {
+    let (runtime, mut openvr) = backend();
+    runtime.fail_next_reinit(OpenVRError::AlreadyInitialized);
+
+    assert_eq!(openvr.restart(), Err(OpenVRError::AlreadyInitialized));
+    assert!(!openvr.is_connected());
+
+    openvr.restart().unwrap();
+    assert!(openvr.is_connected());
+}
+
+#[test]
+fn quitting_disconnects_until_restarted() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(0, TrackedDeviceClass::HMD);
+    openvr.wait();
+    new_trackers(&mut openvr);
+    take_events(&openvr);
+
+    runtime.push_event(0, quit());
+    openvr.wait();
+    assert!(!openvr.is_connected());
+    assert_eq!(
+        take_events(&openvr),
+        vec![OpenVREvent::QuitRequested, OpenVREvent::RuntimeDisconnected]
+    );
+    assert_eq!(openvr.get_removed_trackers(), Some(vec![0]));
+    assert!(!openvr.get_tracker_position(0).valid);
+
+    // No reconnecting behind the application's back
+    openvr.wait();
+    assert!(!openvr.is_connected());
+    assert!(openvr.is_quitting());
+    assert!(take_events(&openvr).is_empty());
+
+    // Public entry points keep working while disconnected
+    assert!(openvr.get_area().is_empty());
+    assert!(openvr.get_hidden_area_mesh().is_empty());
+    assert!(openvr.get_tracker_properties(0).is_none());
+    assert_eq!(
+        openvr.add_action("/actions/main/in/trigger", ActionKind::Digital),
+        Err(InputError::Disconnected)
+    );
+
+    openvr.restart().unwrap();
+    assert!(openvr.is_connected());
+    assert!(!openvr.is_quitting());
+    openvr.wait();
+    assert_eq!(new_trackers(&mut openvr), vec![0]);
+}
+
+#[test]
+fn failed_wait_keeps_the_last_poses() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(0, TrackedDeviceClass::HMD);
+    runtime.set_pose(
+        0,
+        DevicePose {
+            device_to_absolute_tracking: translated(0.0, 1.5, 0.0),
+            tracking_result: TrackingResult::RunningOk,
+            pose_is_valid: true,
+            device_is_connected: true,
+            ..DevicePose::default()
+        },
+    );
+    openvr.wait();
+
+    // A few failures aren't enough to consider the runtime lost
+    let request_failed = CompositorError(sys::EVRCompositorError_VRCompositorError_RequestFailed);
+    runtime.fail_next_wait(request_failed);
+    runtime.fail_next_wait(request_failed);
+    openvr.wait();
+    openvr.wait();
+
+    assert!(openvr.is_connected());
+    assert!(take_events(&openvr).is_empty());
+    let position = openvr.get_tracker_position(0);
+    assert!(position.valid);
+    assert_near(position.position.into(), [0.0, 1.5, 0.0]);
+}
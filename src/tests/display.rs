@@ -0,0 +1,67 @@
+use amethyst::xr::XRBackend;
+
+use super::{assert_near, backend, translated};
+use {Eye, HiddenAreaMeshType, TrackingUniverseOrigin};
+
+#[test]
+fn area_follows_the_tracking_origin() {
+    let (runtime, mut openvr) = backend();
+    let rect = [
+        [1.0, 0.0, 2.0],
+        [2.0, 0.0, 2.0],
+        [2.0, 0.0, 3.0],
+        [1.0, 0.0, 3.0],
+    ];
+    runtime.set_seated_zero_pose(translated(1.0, 0.0, 2.0));
+
+    // Nothing to report while the chaperone isn't calibrated
+    runtime.set_chaperone(false, Some(rect), None);
+    assert!(openvr.get_area().is_empty());
+
+    runtime.set_chaperone(true, Some(rect), None);
+    assert_eq!(openvr.get_area(), rect.to_vec());
+
+    // Collision bounds take precedence over the play area
+    let bounds = vec![[0.0, 0.0, 0.0], [0.0, 2.0, 0.0], [1.0, 2.0, 0.0]];
+    runtime.set_chaperone(true, Some(rect), Some(bounds.clone()));
+    assert_eq!(openvr.get_area(), bounds);
+
+    runtime.set_chaperone(true, Some(rect), None);
+    openvr.set_tracking_origin(TrackingUniverseOrigin::Seated);
+    let area = openvr.get_area();
+    assert_near(area[0], [0.0, 0.0, 0.0]);
+    assert_near(area[2], [1.0, 0.0, 1.0]);
+
+    openvr.set_tracking_origin(TrackingUniverseOrigin::RawAndUncalibrated);
+    assert!(openvr.get_area().is_empty());
+}
+
+#[test]
+fn hidden_area_mesh_is_in_device_coordinates() {
+    let (runtime, mut openvr) = backend();
+    let left = vec![[0.0, 0.0], [1.0, 0.0], [0.0, 0.5]];
+    let right = vec![[1.0, 1.0], [0.5, 1.0], [1.0, 0.0]];
+    runtime.set_hidden_area_mesh(Eye::Left, HiddenAreaMeshType::Standard, left);
+    runtime.set_hidden_area_mesh(Eye::Right, HiddenAreaMeshType::Standard, right);
+
+    assert_eq!(
+        openvr.hidden_area_mesh(Eye::Left, HiddenAreaMeshType::Standard),
+        vec![[-1.0, 1.0], [1.0, 1.0], [-1.0, 0.0]]
+    );
+    assert!(openvr
+        .hidden_area_mesh(Eye::Left, HiddenAreaMeshType::LineLoop)
+        .is_empty());
+
+    // Both eyes are packed into one mesh, tagged with their target index
+    assert_eq!(
+        openvr.get_hidden_area_mesh(),
+        vec![
+            [-1.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [-1.0, 0.0, 0.0],
+            [1.0, -1.0, 1.0],
+            [0.0, -1.0, 1.0],
+            [1.0, 1.0, 1.0],
+        ]
+    );
+}
@@ -0,0 +1,69 @@
+use std::process;
+
+use amethyst::xr::XRBackend;
+use openvr::system::event::{Event, Process};
+use openvr::TrackedDeviceClass;
+
+use super::{backend, new_trackers, quit, take_events};
+use runtime::mock::MockRuntime;
+use {OpenVR, OpenVREvent};
+
+#[test]
+fn runtime_events_are_published() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(1, TrackedDeviceClass::Controller);
+    openvr.wait();
+    new_trackers(&mut openvr);
+    take_events(&openvr);
+
+    runtime.push_event(1, Event::TrackedDeviceActivated);
+    runtime.push_event(1, Event::DashboardActivated);
+    runtime.push_event(1, Event::TrackedDeviceDeactivated);
+    openvr.wait();
+
+    assert_eq!(
+        take_events(&openvr),
+        vec![
+            OpenVREvent::TrackerActivated(1),
+            OpenVREvent::DashboardActivated,
+            OpenVREvent::TrackerDeactivated(1),
+        ]
+    );
+}
+
+#[test]
+fn events_are_dropped_without_a_system() {
+    let runtime = MockRuntime::new();
+    let mut openvr = OpenVR::with_runtime(runtime.clone());
+
+    runtime.push_event(1, Event::DashboardActivated);
+    openvr.wait();
+    assert!(take_events(&openvr).is_empty());
+}
+
+#[test]
+fn quit_requests_are_acknowledged() {
+    let (runtime, mut openvr) = backend();
+
+    // Other applications being asked to quit don't matter
+    runtime.push_event(
+        0,
+        Event::ProcessQuit(Process {
+            pid: process::id() + 1,
+            old_pid: 0,
+            forced: false,
+        }),
+    );
+    openvr.wait();
+    assert!(!openvr.is_quitting());
+    assert!(take_events(&openvr).is_empty());
+
+    runtime.push_event(0, quit());
+    openvr.wait();
+    assert!(runtime.quit_acknowledged());
+    assert!(openvr.is_quitting());
+    assert_eq!(take_events(&openvr)[0], OpenVREvent::QuitRequested);
+
+    openvr.submit_gl_target(0, 1);
+    assert!(runtime.take_submitted().is_empty());
+}
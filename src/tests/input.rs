@@ -0,0 +1,187 @@
+use amethyst::xr::XRBackend;
+use openvr::TrackedDeviceClass;
+
+use super::{backend, new_trackers, quit};
+use {
+    ActionKind, ActionState, ButtonId, ControllerState, DigitalActionData, HapticRequest,
+    HapticTarget, InputError, OpenVRControllerStates, BUTTON_GRIP, BUTTON_TRIGGER,
+};
+
+fn pressing(button: ButtonId) -> ControllerState {
+    ControllerState {
+        packet_num: 1,
+        button_pressed: 1 << button as u64,
+        ..ControllerState::default()
+    }
+}
+
+#[test]
+fn actions_are_read_every_frame() {
+    let (runtime, mut openvr) = backend();
+    assert_eq!(
+        openvr.add_action_set("/actions/main"),
+        Err(InputError::Unavailable)
+    );
+
+    runtime.set_input_available(true);
+    openvr.load_action_manifest("/actions.json").unwrap();
+    assert_eq!(
+        runtime.action_manifest_path().unwrap().to_str().unwrap(),
+        "/actions.json"
+    );
+    openvr.add_action_set("/actions/main").unwrap();
+    openvr
+        .add_action("/actions/main/in/trigger", ActionKind::Digital)
+        .unwrap();
+    assert_eq!(
+        openvr.add_action("/actions/main/in/\0", ActionKind::Digital),
+        Err(InputError::UnknownAction)
+    );
+
+    let pressed = DigitalActionData {
+        active: true,
+        state: true,
+        changed: true,
+    };
+    runtime.set_action_state("/actions/main/in/trigger", ActionState::Digital(pressed));
+    openvr.wait();
+
+    assert_eq!(runtime.updated_action_sets(), vec!["/actions/main"]);
+    match openvr
+        .shared
+        .lock()
+        .action_states
+        .get("/actions/main/in/trigger")
+    {
+        Some(&ActionState::Digital(data)) => assert_eq!(data, pressed),
+        _ => panic!("expected the digital action state"),
+    }
+
+    // Handles stay usable after reconnecting
+    openvr.restart().unwrap();
+    openvr.wait();
+    assert_eq!(runtime.updated_action_sets(), vec!["/actions/main"]);
+    assert!(openvr
+        .shared
+        .lock()
+        .action_states
+        .contains_key("/actions/main/in/trigger"));
+}
+
+#[test]
+fn controller_states_are_polled_for_registered_trackers() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(1, TrackedDeviceClass::Controller);
+    runtime.set_controller_state(1, pressing(BUTTON_TRIGGER));
+    openvr.wait();
+
+    // Only available to systems once the tracker is registered
+    assert!(openvr.shared.lock().controller_states.is_empty());
+    assert!(openvr
+        .get_controller_state(1)
+        .unwrap()
+        .is_pressed(BUTTON_TRIGGER));
+    assert!(openvr.get_controller_state(2).is_none());
+
+    new_trackers(&mut openvr);
+    openvr.wait();
+    let state = openvr.shared.lock().controller_states[&1];
+    assert!(state.is_pressed(BUTTON_TRIGGER));
+    assert!(!state.is_pressed(BUTTON_GRIP));
+    assert!(openvr.shared.lock().controller_poses.contains_key(&1));
+}
+
+#[test]
+fn presses_are_detected_between_frames() {
+    let mut controllers = OpenVRControllerStates::default();
+    controllers.states.insert(1, pressing(BUTTON_TRIGGER));
+    assert!(controllers.was_pressed(1, BUTTON_TRIGGER));
+
+    controllers.previous_states = controllers.states.clone();
+    assert!(controllers.is_pressed(1, BUTTON_TRIGGER));
+    assert!(!controllers.was_pressed(1, BUTTON_TRIGGER));
+
+    controllers.states.insert(1, pressing(BUTTON_GRIP));
+    assert!(controllers.was_released(1, BUTTON_TRIGGER));
+    assert!(controllers.was_pressed(1, BUTTON_GRIP));
+    assert!(!controllers.was_pressed(2, BUTTON_GRIP));
+}
+
+#[test]
+fn legacy_pulses_repeat_until_their_duration_passed() {
+    let (runtime, mut openvr) = backend();
+    openvr.trigger_haptic_pulse(1, 60.0, 0.5);
+    openvr.trigger_haptic_pulse(2, 0.0, 2.0);
+    assert_eq!(
+        runtime.take_haptic_pulses(),
+        vec![(1, 0, 1999), (2, 0, 3999)]
+    );
+
+    // Only the pulse lasting longer than a frame is sent again
+    openvr.wait();
+    assert_eq!(runtime.take_haptic_pulses(), vec![(1, 0, 1999)]);
+    openvr.wait();
+    assert_eq!(runtime.take_haptic_pulses(), vec![(1, 0, 1999)]);
+
+    // Requests from systems are dispatched the same way
+    openvr
+        .shared
+        .lock()
+        .haptic_requests
+        .push(HapticRequest::new(HapticTarget::Tracker(3), 0.0));
+    openvr.wait();
+    assert_eq!(
+        runtime.take_haptic_pulses(),
+        vec![(1, 0, 1999), (3, 0, 3999)]
+    );
+}
+
+#[test]
+fn haptics_are_dropped_while_disconnected() {
+    let (runtime, mut openvr) = backend();
+    runtime.set_input_available(true);
+    openvr
+        .add_action("/actions/main/out/haptic", ActionKind::Digital)
+        .unwrap();
+    openvr
+        .trigger_haptic_vibration("/actions/main/out/haptic", 0.1, 160.0, 1.0)
+        .unwrap();
+    assert_eq!(
+        runtime.take_haptic_vibrations(),
+        vec![(
+            runtime.input_handle("/actions/main/out/haptic"),
+            0.1,
+            160.0,
+            1.0
+        )]
+    );
+    assert_eq!(
+        openvr.trigger_haptic_vibration("/actions/main/out/unknown", 0.1, 160.0, 1.0),
+        Err(InputError::UnknownAction)
+    );
+
+    openvr.trigger_haptic_pulse(0, 60.0, 1.0);
+    runtime.push_event(0, quit());
+    openvr.wait();
+    runtime.take_haptic_pulses();
+    assert_eq!(
+        openvr.trigger_haptic_vibration("/actions/main/out/haptic", 0.1, 160.0, 1.0),
+        Err(InputError::Disconnected)
+    );
+    openvr.trigger_haptic_pulse(0, 0.01, 1.0);
+    assert!(runtime.take_haptic_pulses().is_empty());
+
+    // Requests from systems don't pile up until reconnecting
+    openvr
+        .shared
+        .lock()
+        .haptic_requests
+        .push(HapticRequest::new(HapticTarget::Tracker(0), 0.1));
+    openvr.wait();
+    assert!(openvr.shared.lock().haptic_requests.is_empty());
+
+    // Neither do pulses which were still running
+    openvr.restart().unwrap();
+    openvr.wait();
+    assert!(runtime.take_haptic_pulses().is_empty());
+}
@@ -0,0 +1,81 @@
+mod connection;
+mod display;
+mod events;
+mod input;
+mod render_models;
+mod rotation;
+mod tangents;
+mod trackers;
+mod tracking;
+
+use std::mem;
+
+use openvr::system::event::{Event, Process};
+
+use runtime::mock::MockRuntime;
+use {OpenVR, OpenVREvent};
+
+/// Xorshift generator, so randomized tests are reproducible without pulling in a dependency.
+pub struct Rng(u32);
+
+impl Rng {
+    pub fn new(seed: u32) -> Rng {
+        Rng(seed)
+    }
+
+    /// A uniformly distributed value in `[min, max]`.
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        min + (max - min) * (self.0 as f32 / ::std::u32::MAX as f32)
+    }
+}
+
+/// A backend driven by a mock runtime, publishing events as if an `OpenVRSystem` existed.
+pub fn backend() -> (MockRuntime, OpenVR<MockRuntime>) {
+    let runtime = MockRuntime::new();
+    let openvr = OpenVR::with_runtime(runtime.clone());
+    openvr.shared.lock().publish_events = true;
+    (runtime, openvr)
+}
+
+pub fn take_events(openvr: &OpenVR<MockRuntime>) -> Vec<OpenVREvent> {
+    mem::replace(&mut openvr.shared.lock().events, Vec::new())
+}
+
+/// Registers the trackers connected as of the last `wait`, returning their indices.
+pub fn new_trackers(openvr: &mut OpenVR<MockRuntime>) -> Vec<u32> {
+    use amethyst::xr::XRBackend;
+
+    openvr
+        .get_new_trackers()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// The event the runtime sends when the user quits the application from the dashboard.
+pub fn quit() -> Event {
+    Event::Quit(Process {
+        pid: 0,
+        old_pid: 0,
+        forced: false,
+    })
+}
+
+pub fn translated(x: f32, y: f32, z: f32) -> [[f32; 4]; 3] {
+    [[1.0, 0.0, 0.0, x], [0.0, 1.0, 0.0, y], [0.0, 0.0, 1.0, z]]
+}
+
+pub fn assert_near(actual: [f32; 3], expected: [f32; 3]) {
+    for axis in 0..3 {
+        assert!(
+            (actual[axis] - expected[axis]).abs() < 1e-5,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+}
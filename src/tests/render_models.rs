@@ -0,0 +1,267 @@
+use amethyst::xr::{TrackerComponentModelInfo, TrackerModelLoadStatus, XRBackend};
+use openvr::render_models::Vertex;
+use openvr::TrackedDeviceClass;
+use openvr_sys as sys;
+
+use super::{backend, new_trackers, translated};
+use runtime::mock::MockRuntime;
+use runtime::{
+    ComponentState, DevicePose, RenderModel, RenderModelError, RenderModelTexture, TextureId,
+};
+use {ComponentMaterial, OpenVR, RenderModelProgress};
+
+fn triangle(diffuse_texture_id: Option<TextureId>) -> RenderModel {
+    let vertex = |position, texture_coord| Vertex {
+        position,
+        normal: [0.0, 0.0, 1.0],
+        texture_coord,
+    };
+
+    RenderModel {
+        vertices: vec![
+            vertex([0.0, 0.0, 0.0], [0.0, 0.0]),
+            vertex([1.0, 0.0, 0.0], [1.0, 0.0]),
+            vertex([0.0, 1.0, 0.0], [0.0, 1.0]),
+        ],
+        indices: vec![0, 1, 2],
+        diffuse_texture_id,
+    }
+}
+
+/// Connects a device using the named render model.
+fn connect_with_model(runtime: &MockRuntime, index: u32, model_name: &str) {
+    runtime.connect_device(index, TrackedDeviceClass::Controller);
+    runtime.set_string_property(
+        index,
+        sys::ETrackedDeviceProperty_Prop_RenderModelName_String,
+        model_name,
+    );
+}
+
+fn is_pending(status: TrackerModelLoadStatus) -> bool {
+    match status {
+        TrackerModelLoadStatus::Pending => true,
+        _ => false,
+    }
+}
+
+fn available(status: TrackerModelLoadStatus) -> Vec<TrackerComponentModelInfo> {
+    match status {
+        TrackerModelLoadStatus::Available(infos) => infos,
+        _ => panic!("expected the model to be available"),
+    }
+}
+
+fn progress(openvr: &OpenVR<MockRuntime>, index: u32) -> RenderModelProgress {
+    openvr.shared.lock().render_model_progress[&index]
+}
+
+#[test]
+fn components_load_incrementally() {
+    let (runtime, mut openvr) = backend();
+    connect_with_model(&runtime, 1, "controller");
+    runtime.add_render_model_component("controller", "trigger");
+    runtime.add_render_model("trigger", triangle(None), 0);
+    runtime.add_render_model_component("controller", "body");
+    runtime.add_render_model("body", triangle(None), 2);
+
+    // Components which finished loading are kept while the others are still loading
+    assert!(is_pending(openvr.get_tracker_models(1)));
+    assert_eq!(
+        progress(&openvr, 1),
+        RenderModelProgress {
+            loaded: 1,
+            total: 2,
+        }
+    );
+    assert!(is_pending(openvr.get_tracker_models(1)));
+    assert!(!progress(&openvr, 1).is_complete());
+
+    let infos = available(openvr.get_tracker_models(1));
+    let names: Vec<_> = infos
+        .iter()
+        .map(|info| info.component_name.clone())
+        .collect();
+    assert_eq!(
+        names,
+        vec![Some("trigger".to_owned()), Some("body".to_owned())]
+    );
+    assert_eq!(infos[1].vertices.len(), 3);
+    assert_eq!(infos[1].indices, vec![0, 1, 2]);
+    assert!(progress(&openvr, 1).is_complete());
+}
+
+#[test]
+fn failed_components_fall_back_to_the_whole_model() {
+    let (runtime, mut openvr) = backend();
+    connect_with_model(&runtime, 1, "controller");
+    runtime.add_render_model_component("controller", "missing");
+    runtime.add_render_model("controller", triangle(None), 0);
+
+    let infos = available(openvr.get_tracker_models(1));
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].component_name, None);
+
+    // Devices without a render model, and models which fail, are unavailable
+    runtime.connect_device(2, TrackedDeviceClass::GenericTracker);
+    match openvr.get_tracker_models(2) {
+        TrackerModelLoadStatus::Unavailable => (),
+        _ => panic!("expected no model"),
+    }
+    connect_with_model(&runtime, 3, "broken");
+    runtime.fail_render_model("broken", "Not found");
+    match openvr.get_tracker_models(3) {
+        TrackerModelLoadStatus::Unavailable => (),
+        _ => panic!("expected the model to fail"),
+    }
+}
+
+#[test]
+fn identical_devices_share_loaded_models() {
+    let (runtime, mut openvr) = backend();
+    connect_with_model(&runtime, 1, "controller");
+    connect_with_model(&runtime, 2, "controller");
+    runtime.add_render_model("controller", triangle(Some(3)), 0);
+    runtime.add_texture(
+        3,
+        RenderModelTexture {
+            data: vec![255; 4],
+            size: (1, 1),
+        },
+        0,
+    );
+    let first = available(openvr.get_tracker_models(1));
+
+    // The second device doesn't go back to the runtime, which would fail by now
+    runtime.fail_render_model("controller", "Not found");
+    runtime.add_texture(
+        3,
+        RenderModelTexture {
+            data: vec![0; 4],
+            size: (1, 1),
+        },
+        0,
+    );
+    let second = available(openvr.get_tracker_models(2));
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[0].vertices.len(), first[0].vertices.len());
+    assert_eq!(second[0].texture.as_ref().unwrap().data, vec![255; 4]);
+
+    // The cache belongs to the runtime session
+    openvr.restart().unwrap();
+    match openvr.get_tracker_models(2) {
+        TrackerModelLoadStatus::Unavailable => (),
+        _ => panic!("expected the model to be loaded again"),
+    }
+}
+
+#[test]
+fn component_states_follow_the_tracker() {
+    let (runtime, mut openvr) = backend();
+    connect_with_model(&runtime, 1, "controller");
+    runtime.add_render_model_component("controller", "trigger");
+    runtime.add_render_model_component("controller", "base");
+    runtime.set_pose(
+        1,
+        DevicePose {
+            device_to_absolute_tracking: translated(1.0, 0.0, 0.0),
+            pose_is_valid: true,
+            device_is_connected: true,
+            ..DevicePose::default()
+        },
+    );
+    runtime.set_component_state(
+        1,
+        "trigger",
+        ComponentState {
+            transform: translated(0.0, 0.0, 0.01),
+            is_pressed: true,
+            ..ComponentState::default()
+        },
+    );
+    runtime.set_component_state(
+        1,
+        "base",
+        ComponentState {
+            is_static: true,
+            ..ComponentState::default()
+        },
+    );
+    openvr.wait();
+    assert!(openvr.get_component_states(1).is_empty());
+
+    new_trackers(&mut openvr);
+    openvr.wait();
+    let states = openvr.get_component_states(1);
+    assert_eq!(states.len(), 2);
+    assert_eq!(states[0].name, "trigger");
+    assert!(states[0].pressed);
+    assert!(states[0].visible);
+    assert_eq!(states[0].local_transform.w.z, 0.01);
+    assert_eq!(states[0].tracking_transform.w.x, 1.0);
+    assert_eq!(states[0].tracking_transform.w.z, 0.01);
+
+    // Static components are only queried once
+    runtime.set_component_state(
+        1,
+        "trigger",
+        ComponentState {
+            is_visible: false,
+            ..ComponentState::default()
+        },
+    );
+    runtime.set_component_state(
+        1,
+        "base",
+        ComponentState {
+            is_visible: false,
+            ..ComponentState::default()
+        },
+    );
+    openvr.wait();
+    let states = openvr.get_component_states(1);
+    assert!(!states[0].visible);
+    assert!(!states[0].pressed);
+    assert_eq!(states[1].name, "base");
+    assert!(states[1].visible);
+    assert_eq!(openvr.shared.lock().component_states[&1], states);
+}
+
+#[test]
+fn texture_errors_are_told_apart_from_loading() {
+    let (runtime, mut openvr) = backend();
+    connect_with_model(&runtime, 1, "textured");
+    runtime.add_render_model("textured", triangle(Some(4)), 0);
+    runtime.add_texture(
+        4,
+        RenderModelTexture {
+            data: vec![255; 16],
+            size: (2, 2),
+        },
+        1,
+    );
+
+    // A texture which is still loading holds back the model
+    assert!(is_pending(openvr.get_tracker_models(1)));
+    let infos = available(openvr.get_tracker_models(1));
+    assert_eq!(infos[0].texture.as_ref().unwrap().size, (2, 2));
+    assert_eq!(
+        openvr.shared.lock().render_model_materials[&1],
+        vec![ComponentMaterial {
+            component_name: None,
+            diffuse_texture_id: Some(4),
+            diffuse_texture_size: Some((2, 2)),
+            texture_error: None,
+        }]
+    );
+
+    // A texture which failed leaves the model untextured, with the error to tell why
+    connect_with_model(&runtime, 2, "untextured");
+    runtime.add_render_model("untextured", triangle(Some(5)), 0);
+    let infos = available(openvr.get_tracker_models(2));
+    assert!(infos[0].texture.is_none());
+    assert_eq!(
+        openvr.shared.lock().render_model_materials[&2][0].texture_error,
+        Some(RenderModelError("Texture not found".into()))
+    );
+}
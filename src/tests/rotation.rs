@@ -0,0 +1,114 @@
+use std::f32::consts::PI;
+
+use amethyst::core::cgmath::{InnerSpace, Matrix3, Quaternion, Rad, Rotation3, Vector3};
+
+use super::Rng;
+use matrix_to_quaternion;
+
+/// The row-major 3x4 transform OpenVR would report for a rotation.
+fn to_matrix(q: Quaternion<f32>) -> [[f32; 4]; 3] {
+    // cgmath matrices are column-major
+    let m = Matrix3::from(q);
+    [
+        [m.x.x, m.y.x, m.z.x, 0.0],
+        [m.x.y, m.y.y, m.z.y, 0.0],
+        [m.x.z, m.y.z, m.z.z, 0.0],
+    ]
+}
+
+fn assert_same_rotation(expected: Quaternion<f32>, actual: Quaternion<f32>) {
+    // `q` and `-q` are the same rotation
+    assert!(
+        (expected.dot(actual).abs() - 1.0).abs() < 1e-4,
+        "expected {:?} or its negation, got {:?}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn identity() {
+    let q = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+    assert_same_rotation(q, matrix_to_quaternion(&to_matrix(q)));
+}
+
+#[test]
+fn random_rotations_round_trip() {
+    let mut rng = Rng::new(0x2545_f491);
+
+    for _ in 0..10_000 {
+        let q = Quaternion::new(
+            rng.range(-1.0, 1.0),
+            rng.range(-1.0, 1.0),
+            rng.range(-1.0, 1.0),
+            rng.range(-1.0, 1.0),
+        );
+        if q.magnitude2() < 1e-3 {
+            continue;
+        }
+        let q = q.normalize();
+
+        assert_same_rotation(q, matrix_to_quaternion(&to_matrix(q)));
+    }
+}
+
+#[test]
+fn near_half_turns_round_trip() {
+    let mut rng = Rng::new(0x9e37_79b9);
+    let axes = [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()];
+
+    // Close to 180° the trace is negative, so the largest diagonal element picks the component
+    // that is divided by. Rotating about axes near each unit axis covers every one of them.
+    for (diagonal, &axis) in axes.iter().enumerate() {
+        for _ in 0..1000 {
+            let jitter = Vector3::new(
+                rng.range(-0.3, 0.3),
+                rng.range(-0.3, 0.3),
+                rng.range(-0.3, 0.3),
+            );
+            let angle = Rad(PI - rng.range(0.0, 0.2));
+            let q = Quaternion::from_axis_angle((axis + jitter).normalize(), angle);
+            let m = to_matrix(q);
+
+            assert!(m[0][0] + m[1][1] + m[2][2] <= 0.0);
+            let largest = (0..3)
+                .max_by(|&a, &b| m[a][a].partial_cmp(&m[b][b]).unwrap())
+                .unwrap();
+            assert_eq!(largest, diagonal);
+
+            assert_same_rotation(q, matrix_to_quaternion(&m));
+        }
+    }
+}
+
+#[test]
+fn exact_half_turns_round_trip() {
+    let axes = [
+        Vector3::unit_x(),
+        Vector3::unit_y(),
+        Vector3::unit_z(),
+        Vector3::new(1.0, 1.0, 0.0).normalize(),
+        Vector3::new(0.0, 1.0, 1.0).normalize(),
+        Vector3::new(1.0, 0.0, 1.0).normalize(),
+    ];
+
+    for &axis in &axes {
+        let q = Quaternion::from_axis_angle(axis, Rad(PI));
+        assert_same_rotation(q, matrix_to_quaternion(&to_matrix(q)));
+    }
+}
+
+#[test]
+fn slightly_scaled_matrices_are_normalized() {
+    let q = Quaternion::from_axis_angle(Vector3::new(1.0, 2.0, 3.0).normalize(), Rad(1.0));
+    let mut m = to_matrix(q);
+    for row in m.iter_mut() {
+        for value in row.iter_mut().take(3) {
+            *value *= 1.001;
+        }
+    }
+
+    let result = matrix_to_quaternion(&m);
+    assert!((result.magnitude() - 1.0).abs() < 1e-5);
+    assert_same_rotation(q, result);
+}
@@ -0,0 +1,178 @@
+use amethyst::core::cgmath::{InnerSpace, Vector3};
+use amethyst::xr::TrackerComponentVertex;
+use openvr::render_models::Vertex;
+
+use {convert_vertices, orthogonal_tangent};
+
+fn vertex(position: [f32; 3], normal: [f32; 3], texture_coord: [f32; 2]) -> Vertex {
+    Vertex {
+        position,
+        normal,
+        texture_coord,
+    }
+}
+
+fn assert_near(actual: [f32; 3], expected: [f32; 3]) {
+    let difference = Vector3::from(actual) - Vector3::from(expected);
+    assert!(
+        difference.magnitude() < 1e-5,
+        "expected {:?}, got {:?}",
+        expected,
+        actual
+    );
+}
+
+/// Every tangent has to be usable for normal mapping, whatever the input.
+fn assert_valid_tangents(vertices: &[TrackerComponentVertex]) {
+    for vertex in vertices {
+        let tangent = Vector3::from(vertex.tangent);
+        let normal = Vector3::from(vertex.normal).normalize();
+        assert!(
+            (tangent.magnitude() - 1.0).abs() < 1e-5,
+            "tangent {:?} isn't unit length",
+            tangent
+        );
+        assert!(
+            tangent.dot(normal).abs() < 1e-5,
+            "tangent {:?} isn't perpendicular to normal {:?}",
+            tangent,
+            normal
+        );
+    }
+}
+
+/// A unit quad in the XY plane facing +Z, with u increasing along +X and v along `v_direction`.
+fn quad(v_direction: f32) -> Vec<Vertex> {
+    let v = |y: f32| if v_direction > 0.0 { y } else { 1.0 - y };
+    let normal = [0.0, 0.0, 1.0];
+    vec![
+        vertex([0.0, 0.0, 0.0], normal, [0.0, v(0.0)]),
+        vertex([1.0, 0.0, 0.0], normal, [1.0, v(0.0)]),
+        vertex([1.0, 1.0, 0.0], normal, [1.0, v(1.0)]),
+        vertex([0.0, 1.0, 0.0], normal, [0.0, v(1.0)]),
+    ]
+}
+
+const QUAD_INDICES: [u16; 6] = [0, 1, 2, 0, 2, 3];
+
+#[test]
+fn quad_tangents_follow_u() {
+    let vertices = convert_vertices(&quad(1.0), &QUAD_INDICES);
+
+    assert_eq!(vertices.len(), 4);
+    for vertex in &vertices {
+        assert_near(vertex.tangent, [1.0, 0.0, 0.0]);
+    }
+}
+
+#[test]
+fn texture_coordinates_are_flipped_vertically() {
+    let input = quad(1.0);
+    let vertices = convert_vertices(&input, &QUAD_INDICES);
+
+    for (vertex, input) in vertices.iter().zip(&input) {
+        assert_eq!(
+            vertex.tex_coord,
+            [input.texture_coord[0], 1.0 - input.texture_coord[1]]
+        );
+        assert_eq!(vertex.position, input.position);
+        assert_eq!(vertex.normal, input.normal);
+    }
+}
+
+#[test]
+fn flipped_v_keeps_tangents_along_u() {
+    let input = quad(-1.0);
+    let vertices = convert_vertices(&input, &QUAD_INDICES);
+
+    for (vertex, input) in vertices.iter().zip(&input) {
+        assert_near(vertex.tangent, [1.0, 0.0, 0.0]);
+        assert_eq!(vertex.tex_coord[1], 1.0 - input.texture_coord[1]);
+    }
+}
+
+#[test]
+fn collinear_triangle_falls_back_to_perpendicular_tangent() {
+    let normal = [0.0, 0.0, 1.0];
+    let input = vec![
+        vertex([0.0, 0.0, 0.0], normal, [0.0, 0.0]),
+        vertex([1.0, 0.0, 0.0], normal, [1.0, 0.0]),
+        vertex([2.0, 0.0, 0.0], normal, [0.0, 1.0]),
+    ];
+
+    assert_valid_tangents(&convert_vertices(&input, &[0, 1, 2]));
+}
+
+#[test]
+fn zero_area_uvs_fall_back_to_perpendicular_tangent() {
+    let normal = [0.0, 0.0, 1.0];
+    let input = vec![
+        vertex([0.0, 0.0, 0.0], normal, [0.5, 0.5]),
+        vertex([1.0, 0.0, 0.0], normal, [0.5, 0.5]),
+        vertex([0.0, 1.0, 0.0], normal, [0.5, 0.5]),
+    ];
+
+    let vertices = convert_vertices(&input, &[0, 1, 2]);
+    assert_valid_tangents(&vertices);
+    for vertex in &vertices {
+        assert!(vertex.tangent.iter().all(|value| value.is_finite()));
+    }
+}
+
+#[test]
+fn vertical_normals_get_valid_tangents() {
+    for &up in &[1.0, -1.0] {
+        let normal = [0.0, up, 0.0];
+
+        // A quad in the XZ plane, with u along +X
+        let input = vec![
+            vertex([0.0, 0.0, 0.0], normal, [0.0, 0.0]),
+            vertex([1.0, 0.0, 0.0], normal, [1.0, 0.0]),
+            vertex([1.0, 0.0, 1.0], normal, [1.0, 1.0]),
+            vertex([0.0, 0.0, 1.0], normal, [0.0, 1.0]),
+        ];
+        let vertices = convert_vertices(&input, &QUAD_INDICES);
+        assert_valid_tangents(&vertices);
+        for vertex in &vertices {
+            assert_near(vertex.tangent, [1.0, 0.0, 0.0]);
+        }
+
+        // Without a usable triangle, only the fallback is left
+        let vertices = convert_vertices(&input, &[0, 1, 1]);
+        assert_valid_tangents(&vertices);
+    }
+}
+
+#[test]
+fn unreferenced_and_out_of_range_indices_are_ignored() {
+    let mut input = quad(1.0);
+    input.push(vertex([5.0, 5.0, 5.0], [1.0, 0.0, 0.0], [0.0, 0.0]));
+
+    let vertices = convert_vertices(&input, &[0, 1, 2, 0, 2, 3, 2, 3, 9]);
+    assert_eq!(vertices.len(), 5);
+    assert_valid_tangents(&vertices);
+    assert_near(vertices[0].tangent, [1.0, 0.0, 0.0]);
+}
+
+#[test]
+fn fallback_tangent_is_perpendicular_to_any_axis() {
+    let normals = [
+        [1.0, 0.0, 0.0],
+        [-1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, -1.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [0.0, 0.0, -1.0],
+    ];
+    for &normal in &normals {
+        let normal = Vector3::from(normal);
+        let tangent = orthogonal_tangent(normal, Vector3::new(0.0, 0.0, 0.0));
+        assert!((tangent.magnitude() - 1.0).abs() < 1e-5);
+        assert!(tangent.dot(normal).abs() < 1e-5);
+
+        // A tangent parallel to the normal carries no direction either
+        let tangent = orthogonal_tangent(normal, normal * 2.0);
+        assert!((tangent.magnitude() - 1.0).abs() < 1e-5);
+        assert!(tangent.dot(normal).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,225 @@
+use amethyst::xr::XRBackend;
+use openvr::system::event::Event;
+use openvr::TrackedDeviceClass;
+use openvr_sys as sys;
+
+use super::{assert_near, backend, new_trackers, take_events, translated};
+use runtime::{DevicePose, TrackingResult};
+use {ControllerRole, OpenVREvent};
+
+#[test]
+fn trackers_are_added_and_removed() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(0, TrackedDeviceClass::HMD);
+    runtime.connect_device(3, TrackedDeviceClass::Controller);
+    openvr.wait();
+
+    assert_eq!(new_trackers(&mut openvr), vec![0, 3]);
+    assert!(openvr.get_new_trackers().is_none());
+    assert!(openvr.get_removed_trackers().is_none());
+    assert_eq!(
+        openvr.get_tracker_properties(3).unwrap().class,
+        TrackedDeviceClass::Controller
+    );
+
+    runtime.disconnect_device(3);
+    runtime.connect_device(5, TrackedDeviceClass::GenericTracker);
+    openvr.wait();
+
+    assert_eq!(new_trackers(&mut openvr), vec![5]);
+    assert_eq!(openvr.get_removed_trackers(), Some(vec![3]));
+    assert!(openvr.get_removed_trackers().is_none());
+    assert!(!openvr.shared.lock().trackers.contains_key(&3));
+}
+
+#[test]
+fn tracker_position_follows_the_pose() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(1, TrackedDeviceClass::Controller);
+    runtime.set_pose(
+        1,
+        DevicePose {
+            device_to_absolute_tracking: translated(1.0, 2.0, 3.0),
+            velocity: [0.5, 0.0, 0.0],
+            tracking_result: TrackingResult::RunningOk,
+            pose_is_valid: true,
+            device_is_connected: true,
+            ..DevicePose::default()
+        },
+    );
+
+    // Nothing is known before the first frame
+    assert!(!openvr.get_tracker_position(1).valid);
+
+    openvr.wait();
+    let position = openvr.get_tracker_position(1);
+    assert!(position.valid);
+    assert_near(position.position.into(), [1.0, 2.0, 3.0]);
+    assert_near(position.velocity.into(), [0.5, 0.0, 0.0]);
+    assert!(!openvr.get_tracker_position(2).valid);
+}
+
+#[test]
+fn trackers_beyond_the_sixteenth_are_registered() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(0, TrackedDeviceClass::HMD);
+    runtime.connect_device(20, TrackedDeviceClass::GenericTracker);
+    openvr.wait();
+    assert_eq!(new_trackers(&mut openvr), vec![0, 20]);
+
+    runtime.connect_device(63, TrackedDeviceClass::GenericTracker);
+    openvr.wait();
+    assert_eq!(new_trackers(&mut openvr), vec![63]);
+    assert!(openvr.get_tracker_position(63).valid);
+
+    runtime.disconnect_device(20);
+    openvr.wait();
+    assert_eq!(openvr.get_removed_trackers(), Some(vec![20]));
+}
+
+#[test]
+fn tracker_properties_are_published() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(2, TrackedDeviceClass::GenericTracker);
+    runtime.set_string_property(
+        2,
+        sys::ETrackedDeviceProperty_Prop_SerialNumber_String,
+        "LHR-1",
+    );
+    runtime.set_string_property(
+        2,
+        sys::ETrackedDeviceProperty_Prop_ControllerType_String,
+        "vive_tracker_waist",
+    );
+    openvr.wait();
+    new_trackers(&mut openvr);
+
+    let properties = openvr.shared.lock().trackers[&2].clone();
+    assert_eq!(properties.class, TrackedDeviceClass::GenericTracker);
+    assert_eq!(properties.serial_number, Some("LHR-1".to_owned()));
+    assert_eq!(properties.manufacturer, None);
+    assert_eq!(properties.tracker_role, Some("waist".to_owned()));
+    assert!(openvr.shared.lock().trackers_changed);
+}
+
+#[test]
+fn battery_is_only_read_from_devices_providing_it() {
+    let (runtime, openvr) = backend();
+    runtime.connect_device(1, TrackedDeviceClass::Controller);
+    runtime.set_float_property(
+        1,
+        sys::ETrackedDeviceProperty_Prop_DeviceBatteryPercentage_Float,
+        0.5,
+    );
+    runtime.set_bool_property(
+        1,
+        sys::ETrackedDeviceProperty_Prop_DeviceIsCharging_Bool,
+        true,
+    );
+
+    let properties = openvr.get_tracker_properties(1).unwrap();
+    assert_eq!(properties.battery_percentage, None);
+    assert_eq!(properties.is_charging, None);
+
+    runtime.set_bool_property(
+        1,
+        sys::ETrackedDeviceProperty_Prop_DeviceProvidesBatteryStatus_Bool,
+        true,
+    );
+    let properties = openvr.get_tracker_properties(1).unwrap();
+    assert_eq!(properties.battery_percentage, Some(0.5));
+    assert_eq!(properties.is_charging, Some(true));
+}
+
+#[test]
+fn controller_roles_follow_role_changes() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(1, TrackedDeviceClass::Controller);
+    runtime.connect_device(2, TrackedDeviceClass::Controller);
+    runtime.set_controller_role(1, ControllerRole::LeftHand);
+    openvr.wait();
+    new_trackers(&mut openvr);
+    take_events(&openvr);
+    assert_eq!(
+        openvr.shared.lock().trackers[&1].controller_role,
+        ControllerRole::LeftHand
+    );
+
+    // The user swapped hands
+    runtime.set_controller_role(1, ControllerRole::RightHand);
+    runtime.set_controller_role(2, ControllerRole::LeftHand);
+    runtime.push_event(1, Event::TrackedDeviceRoleChanged);
+    openvr.wait();
+
+    let mut events = take_events(&openvr);
+    assert_eq!(events.remove(0), OpenVREvent::TrackerRoleChanged);
+    // Trackers are kept in a map, so their order isn't fixed
+    assert_eq!(events.len(), 2);
+    assert!(events.contains(&OpenVREvent::ControllerRoleChanged {
+        tracker: 1,
+        role: ControllerRole::RightHand,
+    }));
+    assert!(events.contains(&OpenVREvent::ControllerRoleChanged {
+        tracker: 2,
+        role: ControllerRole::LeftHand,
+    }));
+    assert_eq!(openvr.get_controller_role(2), ControllerRole::LeftHand);
+    assert_eq!(
+        openvr.shared.lock().trackers[&1].controller_role,
+        ControllerRole::RightHand
+    );
+}
+
+fn sys_pose(tracking_result: sys::ETrackingResult) -> sys::TrackedDevicePose_t {
+    sys::TrackedDevicePose_t {
+        mDeviceToAbsoluteTracking: sys::HmdMatrix34_t {
+            m: translated(0.0, 1.0, 0.0),
+        },
+        vVelocity: sys::HmdVector3_t { v: [0.0; 3] },
+        vAngularVelocity: sys::HmdVector3_t { v: [0.0; 3] },
+        eTrackingResult: tracking_result,
+        bPoseIsValid: true,
+        bDeviceIsConnected: true,
+    }
+}
+
+#[test]
+fn tracking_results_are_read_from_the_runtime() {
+    let pose = DevicePose::from(&sys_pose(sys::ETrackingResult_TrackingResult_Running_OK));
+    assert_eq!(pose.tracking_result, TrackingResult::RunningOk);
+    assert_eq!(pose.device_to_absolute_tracking, translated(0.0, 1.0, 0.0));
+    let pose = DevicePose::from(&sys_pose(
+        sys::ETrackingResult_TrackingResult_Calibrating_OutOfRange,
+    ));
+    assert!(pose.tracking_result.is_calibrating());
+    assert!(pose.tracking_result.is_out_of_range());
+    // `TrackingResult_Fallback_RotationOnly`
+    let pose = DevicePose::from(&sys_pose(300));
+    assert_eq!(pose.tracking_result, TrackingResult::FallbackRotationOnly);
+    let pose = DevicePose::from(&sys_pose(12345));
+    assert_eq!(pose.tracking_result, TrackingResult::Uninitialized);
+
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(1, TrackedDeviceClass::Controller);
+    runtime.set_pose(
+        1,
+        DevicePose {
+            tracking_result: TrackingResult::FallbackRotationOnly,
+            pose_is_valid: true,
+            device_is_connected: true,
+            ..DevicePose::default()
+        },
+    );
+    assert_eq!(openvr.get_tracking_result(1), TrackingResult::Uninitialized);
+
+    openvr.wait();
+    assert_eq!(
+        openvr.get_tracking_result(1),
+        TrackingResult::FallbackRotationOnly
+    );
+    assert_eq!(
+        openvr.shared.lock().tracking_results[1],
+        TrackingResult::FallbackRotationOnly
+    );
+    assert_eq!(openvr.get_tracking_result(2), TrackingResult::Uninitialized);
+}
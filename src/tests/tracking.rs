@@ -0,0 +1,162 @@
+use amethyst::xr::XRBackend;
+use openvr::TrackedDeviceClass;
+use openvr_sys as sys;
+
+use super::{assert_near, backend, quit, take_events, translated};
+use runtime::{DevicePose, TrackingResult};
+use {OpenVREvent, PoseKind, TrackingUniverseOrigin};
+
+#[test]
+fn requested_origin_is_applied_on_wait() {
+    let (runtime, mut openvr) = backend();
+    openvr.shared.lock().requested_tracking_origin = Some(TrackingUniverseOrigin::Seated);
+    assert_eq!(openvr.tracking_origin(), TrackingUniverseOrigin::Standing);
+
+    openvr.wait();
+    assert_eq!(openvr.tracking_origin(), TrackingUniverseOrigin::Seated);
+    assert_eq!(
+        runtime.tracking_space(),
+        Some(TrackingUniverseOrigin::Seated)
+    );
+    assert_eq!(
+        openvr.shared.lock().tracking_origin,
+        Some(TrackingUniverseOrigin::Seated)
+    );
+}
+
+#[test]
+fn origin_carries_over_reconnecting() {
+    let (runtime, mut openvr) = backend();
+    runtime.push_event(0, quit());
+    openvr.wait();
+
+    openvr.set_tracking_origin(TrackingUniverseOrigin::RawAndUncalibrated);
+    assert_eq!(runtime.tracking_space(), None);
+
+    openvr.restart().unwrap();
+    assert_eq!(
+        runtime.tracking_space(),
+        Some(TrackingUniverseOrigin::RawAndUncalibrated)
+    );
+}
+
+#[test]
+fn seated_zero_pose_is_reset_once() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(0, TrackedDeviceClass::HMD);
+    runtime.set_pose(
+        0,
+        DevicePose {
+            device_to_absolute_tracking: translated(0.5, 1.2, 0.0),
+            pose_is_valid: true,
+            device_is_connected: true,
+            ..DevicePose::default()
+        },
+    );
+
+    openvr.reset_seated_zero_pose();
+    assert_eq!(openvr.seated_zero_pose_to_standing().w.x, 0.5);
+    assert_eq!(
+        openvr.shared.lock().seated_to_standing,
+        Some(translated(0.5, 1.2, 0.0))
+    );
+
+    // Announced by the runtime, like when the user recenters from the dashboard
+    assert!(take_events(&openvr).is_empty());
+    openvr.wait();
+    assert_eq!(take_events(&openvr), vec![OpenVREvent::SeatedZeroPoseReset]);
+
+    // Systems request it through `OpenVRTracking`
+    openvr.shared.lock().reset_seated_zero_pose_requested = true;
+    openvr.wait();
+    openvr.wait();
+    assert_eq!(take_events(&openvr), vec![OpenVREvent::SeatedZeroPoseReset]);
+}
+
+#[test]
+fn predictions_are_published_every_frame() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(0, TrackedDeviceClass::HMD);
+    runtime.set_float_property(
+        0,
+        sys::ETrackedDeviceProperty_Prop_DisplayFrequency_Float,
+        100.0,
+    );
+    runtime.set_float_property(
+        0,
+        sys::ETrackedDeviceProperty_Prop_SecondsFromVsyncToPhotons_Float,
+        0.005,
+    );
+    runtime.set_time_since_last_vsync(0.004);
+    runtime.set_pose(
+        0,
+        DevicePose {
+            device_to_absolute_tracking: translated(1.0, 2.0, 3.0),
+            velocity: [0.5, 0.0, 0.0],
+            pose_is_valid: true,
+            device_is_connected: true,
+            ..DevicePose::default()
+        },
+    );
+    assert!((openvr.seconds_to_photons() - 0.011).abs() < 1e-6);
+    let predicted = openvr.get_predicted_tracker_positions(2.0);
+    assert_near(predicted[0].position.into(), [2.0, 2.0, 3.0]);
+
+    // Nothing is predicted for systems until they ask for it
+    openvr.wait();
+    assert!(openvr.shared.lock().predicted_poses.is_empty());
+    assert!((openvr.shared.lock().seconds_to_photons - 0.011).abs() < 1e-6);
+
+    openvr.shared.lock().predicted_seconds = Some(2.0);
+    openvr.wait();
+    let predicted = openvr.shared.lock().predicted_poses[0].device_to_absolute_tracking;
+    assert_eq!(predicted[0][3], 2.0);
+
+    runtime.push_event(0, quit());
+    openvr.wait();
+    assert!(openvr.get_predicted_tracker_positions(2.0).is_empty());
+    assert!(openvr.shared.lock().predicted_poses.is_empty());
+    assert_eq!(openvr.shared.lock().seconds_to_photons, 0.0);
+}
+
+#[test]
+fn game_poses_are_kept_alongside_render_poses() {
+    let (runtime, mut openvr) = backend();
+    runtime.connect_device(1, TrackedDeviceClass::Controller);
+    runtime.set_game_pose(
+        1,
+        DevicePose {
+            device_to_absolute_tracking: translated(0.0, 0.0, -1.0),
+            tracking_result: TrackingResult::RunningOutOfRange,
+            pose_is_valid: true,
+            device_is_connected: true,
+            ..DevicePose::default()
+        },
+    );
+    openvr.wait();
+
+    assert_near(openvr.get_tracker_position(1).position.into(), [0.0; 3]);
+    assert_eq!(openvr.get_tracking_result(1), TrackingResult::RunningOk);
+    let game_position = openvr.get_game_tracker_position(1);
+    assert_near(game_position.position.into(), [0.0, 0.0, -1.0]);
+    assert_eq!(
+        openvr.shared.lock().game_poses[1].device_to_absolute_tracking,
+        translated(0.0, 0.0, -1.0)
+    );
+
+    // Tracker entities can follow the game poses instead
+    openvr.set_tracker_pose_kind(PoseKind::Game);
+    assert_near(
+        openvr.get_tracker_position(1).position.into(),
+        [0.0, 0.0, -1.0],
+    );
+    assert_eq!(
+        openvr.get_tracking_result(1),
+        TrackingResult::RunningOutOfRange
+    );
+    openvr.wait();
+    assert_eq!(
+        openvr.shared.lock().tracking_results[1],
+        TrackingResult::RunningOutOfRange
+    );
+}
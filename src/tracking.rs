@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use amethyst::core::cgmath::{Matrix4, SquareMatrix};
+use amethyst::xr::TrackerPositionData;
+use openvr::TrackingUniverseOrigin;
+
+use position_data;
+use runtime::{DevicePose, TrackingResult};
+
+/// Which of the poses returned by the compositor each frame to use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoseKind {
+    /// Predicted for when the upcoming frame is displayed, for rendering.
+    Render,
+    /// Predicted one frame further, for gameplay logic such as physics.
+    Game,
+}
+
+/// The state of one render model component of a tracker, for animating buttons, triggers and
+/// thumbsticks in sync with input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackerComponentState {
+    /// Matches `TrackerComponentModelInfo::component_name`.
+    pub name: String,
+    /// Where to draw the component's model relative to the tracker.
+    pub local_transform: Matrix4<f32>,
+    /// Where to draw the component's model in the tracking universe, based on the tracker's pose
+    /// selected by `OpenVR::set_tracker_pose_kind`.
+    pub tracking_transform: Matrix4<f32>,
+    pub visible: bool,
+    pub touched: bool,
+    pub pressed: bool,
+    pub scrolled: bool,
+}
+
+/// Resource for inspecting and changing the tracking universe origin at runtime. Kept up to date
+/// by the `OpenVRSystem`, changes are applied during the next `wait`.
+pub struct OpenVRTracking {
+    pub(crate) origin: TrackingUniverseOrigin,
+    pub(crate) requested_origin: Option<TrackingUniverseOrigin>,
+    pub(crate) seated_to_standing: Matrix4<f32>,
+    pub(crate) reset_seated_zero_pose_requested: bool,
+    pub(crate) game_poses: Vec<DevicePose>,
+    pub(crate) seconds_to_photons: f32,
+    pub(crate) predicted_seconds: Option<f32>,
+    pub(crate) predicted_poses: Vec<DevicePose>,
+    pub(crate) tracking_results: Vec<TrackingResult>,
+    pub(crate) component_states: HashMap<u32, Vec<TrackerComponentState>>,
+}
+
+impl Default for OpenVRTracking {
+    fn default() -> Self {
+        OpenVRTracking {
+            origin: TrackingUniverseOrigin::Standing,
+            requested_origin: None,
+            seated_to_standing: Matrix4::identity(),
+            reset_seated_zero_pose_requested: false,
+            game_poses: Vec::new(),
+            seconds_to_photons: 0.0,
+            predicted_seconds: None,
+            predicted_poses: Vec::new(),
+            tracking_results: Vec::new(),
+            component_states: HashMap::new(),
+        }
+    }
+}
+
+impl OpenVRTracking {
+    /// The origin poses and the play area are currently reported relative to.
+    pub fn origin(&self) -> TrackingUniverseOrigin {
+        self.origin
+    }
+
+    pub fn set_origin(&mut self, origin: TrackingUniverseOrigin) {
+        self.requested_origin = Some(origin);
+    }
+
+    /// The game pose of a tracker for the current frame, which is predicted one frame further
+    /// than the render pose. Tracker transforms use the render pose unless
+    /// `OpenVR::set_tracker_pose_kind` selected the game pose.
+    pub fn game_tracker_position(&self, index: u32) -> TrackerPositionData {
+        position_data(self.game_poses.get(index as usize))
+    }
+
+    /// Seconds from the start of this frame until it is shown on the HMD, see
+    /// `OpenVR::seconds_to_photons`. 0 while disconnected.
+    pub fn seconds_to_photons(&self) -> f32 {
+        self.seconds_to_photons
+    }
+
+    /// Requests tracker positions predicted `seconds` ahead of each frame's `wait`, for systems
+    /// running at a different rate than rendering, like physics or audio. They are available
+    /// from `predicted_tracker_position` starting with the next frame and updated every frame
+    /// until `None` is set. Add `seconds_to_photons` to predict relative to when the frame is
+    /// displayed instead.
+    pub fn set_predicted_seconds(&mut self, seconds: Option<f32>) {
+        self.predicted_seconds = seconds;
+    }
+
+    /// The position of a tracker predicted as requested by `set_predicted_seconds`. Invalid if
+    /// nothing is requested or the backend is disconnected.
+    pub fn predicted_tracker_position(&self, index: u32) -> TrackerPositionData {
+        position_data(self.predicted_poses.get(index as usize))
+    }
+
+    /// How well a tracker is tracked this frame, for fading out controllers that are out of range
+    /// or hinting that tracking is still calibrating.
+    pub fn tracking_result(&self, index: u32) -> TrackingResult {
+        self.tracking_results
+            .get(index as usize)
+            .cloned()
+            .unwrap_or(TrackingResult::Uninitialized)
+    }
+
+    /// The state of each render model component of a tracker this frame. Empty for trackers
+    /// without components.
+    pub fn component_states(&self, index: u32) -> &[TrackerComponentState] {
+        self.component_states
+            .get(&index)
+            .map_or(&[], |states| states.as_slice())
+    }
+
+    /// Transform from the seated to the standing tracking space.
+    pub fn seated_to_standing(&self) -> Matrix4<f32> {
+        self.seated_to_standing
+    }
+
+    /// Recenters the seated zero pose on the current HMD pose, for "recenter view" buttons. A
+    /// `OpenVREvent::SeatedZeroPoseReset` is sent once it has been applied.
+    pub fn reset_seated_zero_pose(&mut self) {
+        self.reset_seated_zero_pose_requested = true;
+    }
+}
+
+/// Transforms a point by the inverse of a rigid transform.
+#[inline]
+pub(crate) fn inverse_transform_point(m: &[[f32; 4]; 3], p: [f32; 3]) -> [f32; 3] {
+    let d = [p[0] - m[0][3], p[1] - m[1][3], p[2] - m[2][3]];
+    [
+        m[0][0] * d[0] + m[1][0] * d[1] + m[2][0] * d[2],
+        m[0][1] * d[0] + m[1][1] * d[1] + m[2][1] * d[2],
+        m[0][2] * d[0] + m[1][2] * d[1] + m[2][2] * d[2],
+    ]
+}